@@ -3,13 +3,18 @@ use drones2::solution::Solution;
 
 use std::path::Path;
 use std::time::Instant;
-use drones2::metrics;
 use drones2::operators::{INSERTION_OPERATORS, REMOVAL_OPERATORS};
-use drones2::search::alns::{ScoreParams, ALNS};
+use drones2::search::alns::{IslandParams, ScoreParams, ALNS};
+use rand::Rng;
 
 const MAX_ITERATIONS: usize = 10_000;
 const RUNS: usize = 10;
 
+// `RUNS` are now launched as `RUNS` parallel ALNS islands (see
+// ALNS::run_islands) instead of `RUNS` sequential single-worker runs.
+const MIGRATION_INTERVAL: usize = 200;
+const MIGRATION_GAP: i32 = 50;
+
 const SEGMENT_LENGTH: usize = 100;
 const RHO : f32 = 0.2;
 const SCORE_PARAMETERS: ScoreParams = ScoreParams {
@@ -18,6 +23,7 @@ const SCORE_PARAMETERS: ScoreParams = ScoreParams {
     novelty: 10.0,
 };
 const FINAL_TEMPERATURE: f32 = 0.1;
+const CACHE_CAPACITY: usize = 10_000;
 
 const DATA: [&str; 6] = [
     "Call_7_Vehicle_3.txt",
@@ -52,60 +58,49 @@ fn main() -> std::io::Result<()> {
         println!("Initial: {:?}", initial.feasible(&problem));
         println!("Cost: {:?}", initial_cost);
 
-        let mut results = Vec::with_capacity(RUNS);
-        let mut global_metrics = Vec::with_capacity(RUNS);
-
         let start_time = Instant::now();
 
-        for _ in 0..RUNS {
-            let mut metrics = Vec::with_capacity(MAX_ITERATIONS);
-
-            let mut alns = ALNS::new(&operator_combinations, RHO, SEGMENT_LENGTH, SCORE_PARAMETERS, FINAL_TEMPERATURE);
-            
-            let (best_cost, solution) = alns.run(&problem, initial.clone(), MAX_ITERATIONS, Some(&mut metrics));
-
-            results.push((best_cost, solution.to_pylist(true)));
-            global_metrics.push(metrics);
-        }
+        let run_seed: u64 = rand::rng().random();
+        println!("Seed: {}", run_seed);
+
+        // `run_islands` spawns its own threads internally and doesn't take a
+        // `MetricsWriter`, so switching from RUNS sequential single-worker
+        // runs to one RUNS-worker island run drops the per-run Parquet
+        // instrumentation those sequential runs used to write -- an accepted
+        // tradeoff for actually parallelizing the workers the request asked
+        // for, rather than threading a `Mutex<MetricsWriter>` through
+        // run_islands just to keep it.
+        let island_params = IslandParams {
+            n_workers: RUNS,
+            migration_interval: MIGRATION_INTERVAL,
+            migration_gap: MIGRATION_GAP,
+        };
+        let (best_cost, solution) = ALNS::run_islands(
+            &operator_combinations,
+            RHO,
+            SEGMENT_LENGTH,
+            SCORE_PARAMETERS,
+            FINAL_TEMPERATURE,
+            CACHE_CAPACITY,
+            &problem,
+            initial.clone(),
+            MAX_ITERATIONS,
+            None,
+            island_params,
+            run_seed,
+        );
 
         let duration = start_time.elapsed();
 
-        results.sort_by_key(|(cost, _)| *cost);
+        println!("Time computing: {:?} ({:?} setup)", duration, start_time - setup_time);
 
-        println!("Time computing: {:?} ({:?} setup)",
-                 (duration / RUNS as u32) + (start_time - setup_time),
-                 start_time - setup_time);
-
-        if !results.is_empty() {
-            println!(
-                "Average cost: {:?}",
-                results.iter().map(|(cost, _)| cost).sum::<i32>() / results.len() as i32
-            );
-        }
-
-        println!("Best cost: {:?}", results.first().unwrap().0);
-        println!("Best solution: {:?}", results.first().unwrap().1);
+        println!("Best cost: {:?}", best_cost);
+        println!("Best solution: {:?}", solution.to_pylist(true));
 
         println!(
             "Improvement over initial: {:?}",
-            (initial_cost - results.first().unwrap().0) as f64 / initial_cost as f64 * 100.0
+            (initial_cost - best_cost) as f64 / initial_cost as f64 * 100.0
         );
-
-        global_metrics
-            .iter()
-            .enumerate()
-            .for_each(|(i, metric)| {
-                let base_name = if let Some(dot_index) = file.rfind('.') {
-                    &file[..dot_index]
-                } else {
-                    file
-                };
-
-                metrics::serialize_to_parquet(
-                    metric,
-                    format!("instrumentation/annealing_{}_{:03}.parquet", base_name, i).as_str()
-                ).unwrap();
-            });
     }
 
     Ok(())
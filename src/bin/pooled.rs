@@ -2,13 +2,28 @@ use std::io::{self, Write};
 use std::time::Instant;
 use drones2::operators::{INSERTION_OPERATORS, REMOVAL_OPERATORS};
 use drones2::operators::params::RemovalParams; // Import RemovalParams
-use drones2::problem::Problem;
-use drones2::search::pooled::Pooled;
+use drones2::problem::clustering::ClusteringPolicy;
+use drones2::problem::{Problem, TimeWindowPolicy};
+use drones2::search::annealing::{IslandParams, IslandTopology};
+use drones2::search::pooled::{self, AdaptiveWeightParams, CacheParams, IslandStart, Pooled};
 use drones2::search::warmup::Warmup;
-use drones2::solution::Solution;
-use drones2::types::Cost;
+use drones2::solution::{CompletionObjective, ConstructionMode, Solution};
+use drones2::types::{CallId, Cost, VehicleId};
 use drones2::utils::{Args, Parser, enumerate_input_files};
 
+const ADAPTIVE_WEIGHT_PARAMETERS: AdaptiveWeightParams = AdaptiveWeightParams {
+    sigma1: 20.0,
+    sigma2: 1.0,
+    sigma3: 0.5,
+    r: 0.2,
+    segment_length: 100,
+};
+
+const CACHE_PARAMETERS: CacheParams = CacheParams {
+    capacity: 10_000,
+    tabu_window: 50,
+};
+
 fn main() -> io::Result<()> {
     let args = Args::parse();
 
@@ -30,8 +45,24 @@ fn main() -> io::Result<()> {
         assignment_bias: args.removal_assignment_bias,
         min_removals: args.removal_min_removals,
         max_removals: args.removal_max_removals,
+        shaw_pickup_weight: 0.3,
+        shaw_delivery_weight: 0.25,
+        shaw_time_weight: 0.15,
+        shaw_load_weight: 0.1,
+        shaw_compatibility_weight: 0.1,
+        shaw_vehicle_weight: 0.1,
+        shaw_determinism: 6.0,
+        worst_exp: 4.0,
+        worst_skip: 2,
+        cluster_adjacency_threshold: 0.15,
     };
 
+    let island_params = IslandParams {
+        n_islands: args.islands,
+        migration_interval: args.migration_interval,
+        migration_probability: 0.3,
+        topology: if args.ring_topology { IslandTopology::Ring } else { IslandTopology::FullyConnected },
+    };
 
     for path in instance_files {
         let instance_path = match path.to_str() {
@@ -44,7 +75,7 @@ fn main() -> io::Result<()> {
 
         let setup_time = Instant::now();
 
-        let problem = match Problem::load(instance_path) {
+        let mut problem = match Problem::load_with_cache(instance_path, args.cache_dir.as_deref(), args.force_recompute_index) {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("Failed to load problem '{}': {}", instance_path, e);
@@ -52,13 +83,74 @@ fn main() -> io::Result<()> {
             }
         };
 
-        let mut initial = Solution::new(&problem);
-        let initial_cost = initial.cost(&problem);
+        // `--completion-time-objective` measures arrival back at the depot,
+        // so it implies closing the cost model with a final depot leg.
+        if args.return_to_depot || args.completion_time_objective {
+            problem.return_to_depot = true;
+        }
+
+        if let Some(late_penalty) = args.soft_time_window_late_penalty {
+            problem.time_window_policy = TimeWindowPolicy::Soft {
+                late_penalty,
+                early_penalty: args.soft_time_window_early_penalty,
+            };
+        }
+
+        let completion_aggregation = if args.completion_time_makespan {
+            CompletionObjective::Max
+        } else {
+            CompletionObjective::Sum
+        };
+
+        let objective_of = |solution: &mut Solution, problem: &Problem| -> Cost {
+            if args.completion_time_objective {
+                solution.completion_time(problem, completion_aggregation) as Cost
+            } else {
+                solution.cost(problem)
+            }
+        };
+
+        // With --cluster, the search runs over a reduced instance (see
+        // Problem::cluster); cluster_map then expands its routes back to
+        // the original call ids for reporting.
+        let (clustered_problem, cluster_map) = if args.cluster {
+            let policy = ClusteringPolicy {
+                duration_threshold: args.cluster_duration_threshold,
+                distance_threshold: args.cluster_distance_threshold,
+                min_window_overlap: args.cluster_min_window_overlap,
+                max_cluster_size: args.cluster_max_size,
+            };
+            let (reduced, map) = problem.cluster(&policy);
+            (Some(reduced), Some(map))
+        } else {
+            (None, None)
+        };
+        let solve_problem = clustered_problem.as_ref().unwrap_or(&problem);
+
+        let to_original_space = |solution: &Solution| -> Solution {
+            match &cluster_map {
+                Some(map) => {
+                    let vehicle_routes: Vec<Vec<CallId>> = (0..problem.n_vehicles.get() as usize)
+                        .map(|v| map.expand(&solution.route(VehicleId::from_index(v).unwrap())))
+                        .collect();
+                    Solution::from_vehicle_routes(&problem, vehicle_routes)
+                        .expect("expanded cluster routes must reassemble into a valid solution")
+                }
+                None => solution.clone(),
+            }
+        };
+
+        let mut initial = if args.beam_width > 1 {
+            Solution::construct(solve_problem, ConstructionMode::Beam { width: args.beam_width }, &mut rand::rng())
+        } else {
+            Solution::new(solve_problem)
+        };
+        let initial_cost = objective_of(&mut initial, solve_problem);
 
         println!("------");
 
         println!("Instance: {:?}", instance_path);
-        println!("Initial: {:?}", initial.feasible(&problem));
+        println!("Initial: {:?}", initial.feasible(solve_problem));
         println!("Initial cost: {:?}", initial_cost);
 
         let mut results = Vec::with_capacity(runs);
@@ -73,7 +165,7 @@ fn main() -> io::Result<()> {
 
             let t0 = args.t0.unwrap_or_else(|| {
                 let warmup = Warmup::new(&operator_combinations);
-                warmup.run(&problem, current_best_sol.clone(), 100, 0.8)
+                warmup.run(solve_problem, current_best_sol.clone(), 100, 0.8)
             });
             
             let mut temp = t0;
@@ -82,7 +174,7 @@ fn main() -> io::Result<()> {
 
             let mut iterations_per_sec = 1_000;
 
-            let mut search = Pooled::new(&operator_combinations, removal_params);
+            let mut search = Pooled::new(&operator_combinations, removal_params, ADAPTIVE_WEIGHT_PARAMETERS, CACHE_PARAMETERS);
 
             let start_time = Instant::now();
             let mut iteration_end_time = start_time;
@@ -91,10 +183,36 @@ fn main() -> io::Result<()> {
                 let alpha_per_sec = (args.t_final / t0).powf(1.0 / time_limit as f32);
                 let alpha_per_iter = alpha_per_sec.powf(1.0 / iterations_per_sec as f32);
 
-                let (best_cost, solution) = search.run(&problem, current_best_sol.clone(), iterations_per_sec, temp, alpha_per_iter);
-                
+                // `run_islands` constructs its own `Pooled` per island per
+                // call, so unlike the single-island path below, operator
+                // weights don't carry over between ticks -- an accepted
+                // tradeoff for running islands in parallel here rather than
+                // restructuring this tick loop around a persistent pool of
+                // workers.
+                let mut solution = if args.islands > 1 {
+                    let starts = vec![
+                        IslandStart { solution: current_best_sol.clone(), t0: temp, alpha: alpha_per_iter };
+                        args.islands
+                    ];
+                    let (_, solution, _progresses) = pooled::run_islands(
+                        solve_problem,
+                        starts,
+                        &operator_combinations,
+                        removal_params,
+                        ADAPTIVE_WEIGHT_PARAMETERS,
+                        CACHE_PARAMETERS,
+                        island_params,
+                        iterations_per_sec,
+                    );
+                    solution
+                } else {
+                    let (_, solution, _run_progress) = search.run(solve_problem, current_best_sol.clone(), iterations_per_sec, temp, alpha_per_iter);
+                    solution
+                };
+
+                let best_cost = objective_of(&mut solution, solve_problem);
                 if best_cost < current_best_cost {
-                    current_best_sol = solution.clone();
+                    current_best_sol = solution;
                     current_best_cost = best_cost;
                 }
                 
@@ -115,7 +233,7 @@ fn main() -> io::Result<()> {
                     if let Some((last_cost, last_instant)) = printed_cost {
                         if current_best_cost < last_cost && clock.duration_since(last_instant).as_secs() > delay as u64 {
                             println!("\rBest after {:.2} seconds ({:?}): {:?}                                ",
-                                     clock.duration_since(last_instant).as_secs_f64() - delay as f64, current_best_cost, current_best_sol.to_pylist(true));
+                                     clock.duration_since(last_instant).as_secs_f64() - delay as f64, current_best_cost, to_original_space(&current_best_sol).to_pylist(true));
 
                             printed_cost = Some((current_best_cost, clock))
                         }
@@ -132,7 +250,9 @@ fn main() -> io::Result<()> {
                 temp *= alpha_per_sec;
             }
 
-            results.push((current_best_cost, current_best_sol.to_pylist(true)));
+            let mut reported_sol = to_original_space(&current_best_sol);
+            let reported_cost = objective_of(&mut reported_sol, &problem);
+            results.push((reported_cost, reported_sol.to_pylist(true)));
         }
 
         let duration = runs_start_time.elapsed();
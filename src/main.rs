@@ -1,11 +1,16 @@
 use drones2::problem::Problem;
-use drones2::solution::Solution;
+use drones2::solution::{ConstructionMode, Solution};
 use drones2::search::local::local_search;
-use drones2::search::annealing::simulated_annealing;
+use drones2::search::acceptance::AcceptanceStrategy;
+use drones2::search::annealing::{simulated_annealing, IslandParams, IslandTopology};
+use drones2::search::progress::StopConditions;
 
 use std::path::Path;
-use std::time::Instant;
-use drones2::metrics;
+use std::time::{Duration, Instant};
+use drones2::metrics::MetricsWriter;
+
+const METRICS_FLUSH_EVERY: usize = 1_000;
+const METRICS_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
 
 fn main() -> std::io::Result<()> {
     let data = [
@@ -20,6 +25,21 @@ fn main() -> std::io::Result<()> {
     const MAX_ITERATIONS: usize = 1_000;
     const RUNS: usize = 10;
 
+    // Parallel multi-start: each of the RUNS runs below is itself
+    // N_ISLANDS annealing islands migrating toward each other, instead of
+    // a single-island chain.
+    const N_ISLANDS: usize = 4;
+    const MIGRATION_INTERVAL: usize = 200;
+    const MIGRATION_PROBABILITY: f64 = 0.3;
+    const ISLAND_TOPOLOGY: IslandTopology = IslandTopology::FullyConnected;
+
+    let island_params = IslandParams {
+        n_islands: N_ISLANDS,
+        migration_interval: MIGRATION_INTERVAL,
+        migration_probability: MIGRATION_PROBABILITY,
+        topology: ISLAND_TOPOLOGY,
+    };
+
     for file in data {
         let path = Path::new("data").join(file);
 
@@ -27,7 +47,10 @@ fn main() -> std::io::Result<()> {
         
         let problem = Problem::load(path.to_str().unwrap()).unwrap();
 
-        let mut initial = Solution::new(&problem);
+        // Seed the incumbent with regret-2 construction instead of the blank
+        // all-dummy solution, so the SA loop isn't spending its early iterations
+        // just assigning calls that a cheap construction heuristic already places well.
+        let mut initial = Solution::construct(&problem, ConstructionMode::Regret2, &mut rand::rng());
         let initial_cost = initial.cost(&problem);
 
         println!("------");
@@ -44,7 +67,18 @@ fn main() -> std::io::Result<()> {
         for _ in 0..RUNS {
             let mut metrics = Vec::with_capacity(MAX_ITERATIONS);
             
-            let (best_cost, solution) = simulated_annealing(&problem, initial.clone(), MAX_ITERATIONS, 100, 0.1, Some(&mut metrics));
+            let (best_cost, solution) = simulated_annealing(
+                &problem,
+                initial.clone(),
+                MAX_ITERATIONS,
+                100,
+                0.1,
+                AcceptanceStrategy::Metropolis,
+                island_params,
+                StopConditions::default(),
+                None,
+                Some(&mut metrics),
+            );
             //let (best_cost, solution) = local_search(&problem, initial.clone(), MAX_ITERATIONS);
 
             results.push((best_cost, solution.to_pylist(true)));
@@ -74,8 +108,14 @@ fn main() -> std::io::Result<()> {
             (initial_cost - results.first().unwrap().0) as f64 / initial_cost as f64 * 100.0
         );
 
+        // `simulated_annealing` still hands back one fully-materialized
+        // `Vec<IterationRecord>` per run (see its multi-island merge-and-sort
+        // in `search::annealing`), so this path only migrates off the removed
+        // one-shot `serialize_to_parquet` -- it doesn't get bounded-memory
+        // streaming from pushing into a `MetricsWriter` as each iteration
+        // completes.
         global_metrics
-            .iter()
+            .into_iter()
             .enumerate()
             .for_each(|(i, metric)| {
                 let base_name = if let Some(dot_index) = file.rfind('.') {
@@ -83,11 +123,15 @@ fn main() -> std::io::Result<()> {
                 } else {
                     file
                 };
-                
-                metrics::serialize_to_parquet(
-                    metric, 
-                    format!("instrumentation/annealing_{}_{:03}.parquet", base_name, i).as_str()
-                ).unwrap();
+
+                let metrics_path = format!("instrumentation/annealing_{}_{:03}.parquet", base_name, i);
+                let mut writer = MetricsWriter::create(&metrics_path, METRICS_FLUSH_EVERY, METRICS_FLUSH_INTERVAL).unwrap();
+
+                for record in metric {
+                    writer.push(record);
+                }
+
+                writer.close();
             });
     }
 
@@ -1,13 +1,42 @@
-use arrow::array::{BooleanArray, Float64Array, Int64Array};
+use arrow::array::{BooleanArray, Float64Array, Int64Array, UInt64Array};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use parquet::arrow::arrow_writer::ArrowWriter;
 use std::fs::File;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use crate::types::Cost;
 
+/// Outcome of a single iteration's accept/reject decision, for post-hoc
+/// analysis of how often a destroy/repair pair's moves are kept versus
+/// merely improving the incumbent or the global best. Stored in Parquet as
+/// the small integer `code()`, the same way other fixed-vocabulary columns
+/// in this module are encoded, rather than pulling in a string array type
+/// this crate doesn't otherwise need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcceptanceOutcome {
+    Rejected,
+    Accepted,
+    NewIncumbent,
+    NewBest,
+}
+
+impl AcceptanceOutcome {
+    fn code(self) -> i64 {
+        match self {
+            AcceptanceOutcome::Rejected => 0,
+            AcceptanceOutcome::Accepted => 1,
+            AcceptanceOutcome::NewIncumbent => 2,
+            AcceptanceOutcome::NewBest => 3,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct IterationRecord {
+    /// Id of the island (parallel annealing chain) that produced this record;
+    /// always `0` on the single-thread path.
+    pub island: usize,
     pub iteration: usize,
     pub candidate_cost: Cost,
     pub candidate_seen: usize,
@@ -17,30 +46,48 @@ pub struct IterationRecord {
     pub infeasible: usize,
     pub time: f64,
     pub temperature: Option<f32>,
+    /// Snapshot of the reactive operator weights at the time this record was produced.
+    pub operator_weights: Vec<f32>,
+    /// Reward granted to the chosen operator for this iteration (see `operators::mutate::PSI_*`).
+    pub reward: f32,
+    /// Whether this iteration's candidate cost was served from `ALNS`'s
+    /// route-hash memo cache instead of a fresh `candidate.cost` evaluation.
+    pub cache_hit: bool,
+    /// Index of the destroy (removal) operator chosen this iteration, into
+    /// whichever operator list the caller selects from (`REMOVAL_OPERATORS`
+    /// for `ALNS`).
+    pub destroy_op: usize,
+    /// Index of the repair (insertion) operator chosen this iteration. Where
+    /// a search loop couples destroy and repair into a single selectable
+    /// pair (`ALNS::operator_combinations`) rather than choosing them
+    /// independently, this equals `destroy_op`'s partner in that pair; where
+    /// repair is a single fixed operator (`simulated_annealing`, which
+    /// always repairs via `random_placement_all`), this is always `0`.
+    pub repair_op: usize,
+    /// The destroy operator's current adaptive weight; where destroy and
+    /// repair are selected together as one pair, the pair's shared weight.
+    pub destroy_weight: f32,
+    /// The repair operator's current adaptive weight, or `None` where repair
+    /// is a single fixed, unweighted operator (see `repair_op`). Where
+    /// destroy and repair are selected together as one pair, this is the
+    /// same pair weight as `destroy_weight`.
+    pub repair_weight: Option<f32>,
+    /// Whether this iteration's candidate was rejected, accepted as a worse
+    /// move, became the new incumbent, or became the new global best.
+    pub outcome: AcceptanceOutcome,
+    /// Seed drawn from the run's RNG at the start of this iteration, logged
+    /// for correlating a specific iteration's outcome back to its place in
+    /// the run when debugging from the Parquet log; the destroy operators
+    /// and acceptance check still draw from the run's shared RNG (and some
+    /// repair operators from the thread-local RNG) rather than one reseeded
+    /// per iteration, so this doesn't make an
+    /// iteration bit-for-bit replayable on its own.
+    pub seed: u64,
 }
 
-pub fn serialize_to_parquet(
-    iteration_data: &[IterationRecord],
-    filename: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let iterations: Int64Array = iteration_data.iter().map(|d| d.iteration as i64).collect();
-    let candidate_costs: Int64Array = iteration_data.iter().map(|d| d.candidate_cost as i64).collect();
-    let candidate_observations: Int64Array = iteration_data.iter().map(|d| d.candidate_seen as i64).collect();
-    let incumbent_costs: Int64Array = iteration_data.iter().map(|d| d.incumbent_cost as i64).collect();
-    let best_costs: Int64Array = iteration_data.iter().map(|d| d.best_cost as i64).collect();
-    let evaluations: Int64Array = iteration_data.iter().map(|d| d.evaluations as i64).collect();
-    let infeasible_counts: Int64Array = iteration_data
-        .iter()
-        .map(|d| d.infeasible as i64)
-        .collect();
-    let times: Float64Array = iteration_data.iter().map(|d| d.time).collect();
-    let temperatures: Float64Array = iteration_data
-        .iter()
-        .map(|d| d.temperature.unwrap_or(f32::NAN) as f64)
-        .collect();
-
-    // Arrow schema
-    let schema = Schema::new(vec![
+fn iteration_record_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("island", DataType::Int64, false),
         Field::new("iteration", DataType::Int64, false),
         Field::new("candidate_cost", DataType::Int64, false),
         Field::new("candidate_seen", DataType::Int64, false),
@@ -50,11 +97,50 @@ pub fn serialize_to_parquet(
         Field::new("infeasible_count", DataType::Int64, false),
         Field::new("time", DataType::Float64, false),
         Field::new("temperature", DataType::Float64, false),
-    ]);
-    
-    let batch = RecordBatch::try_new(
-        Arc::new(schema),
+        Field::new("destroy_op", DataType::Int64, false),
+        Field::new("repair_op", DataType::Int64, false),
+        Field::new("destroy_weight", DataType::Float64, false),
+        Field::new("repair_weight", DataType::Float64, false),
+        Field::new("outcome", DataType::Int64, false),
+        Field::new("reward", DataType::Float64, false),
+        Field::new("cache_hit", DataType::Boolean, false),
+        Field::new("seed", DataType::UInt64, false),
+    ])
+}
+
+fn iteration_record_batch(
+    schema: &Arc<Schema>,
+    records: &[IterationRecord],
+) -> Result<RecordBatch, Box<dyn std::error::Error>> {
+    let islands: Int64Array = records.iter().map(|r| r.island as i64).collect();
+    let iterations: Int64Array = records.iter().map(|r| r.iteration as i64).collect();
+    let candidate_costs: Int64Array = records.iter().map(|r| r.candidate_cost as i64).collect();
+    let candidate_observations: Int64Array = records.iter().map(|r| r.candidate_seen as i64).collect();
+    let incumbent_costs: Int64Array = records.iter().map(|r| r.incumbent_cost as i64).collect();
+    let best_costs: Int64Array = records.iter().map(|r| r.best_cost as i64).collect();
+    let evaluations: Int64Array = records.iter().map(|r| r.evaluations as i64).collect();
+    let infeasible_counts: Int64Array = records.iter().map(|r| r.infeasible as i64).collect();
+    let times: Float64Array = records.iter().map(|r| r.time).collect();
+    let temperatures: Float64Array = records
+        .iter()
+        .map(|r| r.temperature.unwrap_or(f32::NAN) as f64)
+        .collect();
+    let destroy_ops: Int64Array = records.iter().map(|r| r.destroy_op as i64).collect();
+    let repair_ops: Int64Array = records.iter().map(|r| r.repair_op as i64).collect();
+    let destroy_weights: Float64Array = records.iter().map(|r| r.destroy_weight as f64).collect();
+    let repair_weights: Float64Array = records
+        .iter()
+        .map(|r| r.repair_weight.unwrap_or(f32::NAN) as f64)
+        .collect();
+    let outcomes: Int64Array = records.iter().map(|r| r.outcome.code()).collect();
+    let rewards: Float64Array = records.iter().map(|r| r.reward as f64).collect();
+    let cache_hits: BooleanArray = records.iter().map(|r| r.cache_hit).collect();
+    let seeds: UInt64Array = records.iter().map(|r| r.seed).collect();
+
+    Ok(RecordBatch::try_new(
+        Arc::clone(schema),
         vec![
+            Arc::new(islands),
             Arc::new(iterations),
             Arc::new(candidate_costs),
             Arc::new(candidate_observations),
@@ -64,14 +150,79 @@ pub fn serialize_to_parquet(
             Arc::new(infeasible_counts),
             Arc::new(times),
             Arc::new(temperatures),
+            Arc::new(destroy_ops),
+            Arc::new(repair_ops),
+            Arc::new(destroy_weights),
+            Arc::new(repair_weights),
+            Arc::new(outcomes),
+            Arc::new(rewards),
+            Arc::new(cache_hits),
+            Arc::new(seeds),
         ],
-    )?;
-    
-    let file = File::create(filename)?;
-    let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
-    writer.write(&batch)?;
-    writer.close()?;
+    )?)
+}
 
-    Ok(())
+/// Streams `IterationRecord`s to a Parquet file as they're produced, instead
+/// of holding the whole run's history in memory like the one-shot batch
+/// write this replaces: `push` buffers records and flushes a row group once
+/// `flush_every` have accumulated or `flush_interval` has elapsed since the
+/// last flush, whichever comes first, so a run of millions of iterations
+/// keeps only a bounded window resident at a time.
+pub struct MetricsWriter {
+    writer: ArrowWriter<File>,
+    schema: Arc<Schema>,
+    buffer: Vec<IterationRecord>,
+    flush_every: usize,
+    flush_interval: Duration,
+    last_flush: Instant,
 }
 
+impl MetricsWriter {
+    pub fn create(
+        filename: &str,
+        flush_every: usize,
+        flush_interval: Duration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let schema = Arc::new(iteration_record_schema());
+        let file = File::create(filename)?;
+        let writer = ArrowWriter::try_new(file, Arc::clone(&schema), None)?;
+
+        Ok(MetricsWriter {
+            writer,
+            schema,
+            buffer: Vec::with_capacity(flush_every),
+            flush_every,
+            flush_interval,
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Buffers `record`, flushing a row group if that fills `flush_every` or
+    /// `flush_interval` has elapsed since the last flush.
+    pub fn push(&mut self, record: IterationRecord) {
+        self.buffer.push(record);
+
+        if self.buffer.len() >= self.flush_every || self.last_flush.elapsed() >= self.flush_interval {
+            self.flush();
+        }
+    }
+
+    /// Writes any buffered records as one row group and clears the buffer.
+    pub fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let batch = iteration_record_batch(&self.schema, &self.buffer)
+            .expect("iteration records must convert to a valid record batch");
+        self.writer.write(&batch).expect("failed to write a metrics row group");
+        self.buffer.clear();
+        self.last_flush = Instant::now();
+    }
+
+    /// Flushes any buffered records and finalizes the Parquet file's footer.
+    pub fn close(mut self) {
+        self.flush();
+        self.writer.close().expect("failed to close the metrics Parquet writer");
+    }
+}
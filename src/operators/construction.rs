@@ -1,11 +1,56 @@
 use crate::problem::Problem;
-use crate::types::{CallId, CargoSize, Time, VehicleId};
+use crate::types::{CallId, CargoSize, Cost, Time, VehicleId};
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
 use std::iter::Peekable;
 use std::boxed::Box;
 use std::cmp::Ordering;
 
+use rand::distr::weighted::WeightedIndex;
+use rand::prelude::*;
+
+/// Tunable coefficients for `VehicleState::candidate_weight`: `alpha` scales
+/// the inverse-detour term, `beta` the urgency term (how close the call's
+/// deadline already is), and `gamma` the pending-delivery pull term (how
+/// much picking this candidate helps or hurts the earliest pending
+/// delivery's own slack).
+#[derive(Copy, Clone)]
+pub struct CandidateWeightParams {
+    pub alpha: f32,
+    pub beta: f32,
+    pub gamma: f32,
+}
+
+impl Default for CandidateWeightParams {
+    fn default() -> Self {
+        CandidateWeightParams { alpha: 1.0, beta: 1.0, gamma: 1.0 }
+    }
+}
+
+/// Object-safe `Iterator` that also knows how to clone itself behind a `Box`,
+/// so `VehicleState`'s type-erased event streams can still be duplicated when
+/// a beam-search state is forked into several successors. Blanket-implemented
+/// for any concrete iterator the trees hand out, since those are built from
+/// `BTreeMap::range` and capture nothing, and so are `Clone` already.
+trait CloneableEventIter<'a>: Iterator<Item = (Time, CallId)> + 'a {
+    fn box_clone(&self) -> Box<dyn CloneableEventIter<'a> + 'a>;
+}
+
+impl<'a, T> CloneableEventIter<'a> for T
+where
+    T: Iterator<Item = (Time, CallId)> + Clone + 'a,
+{
+    fn box_clone(&self) -> Box<dyn CloneableEventIter<'a> + 'a> {
+        Box::new(self.clone())
+    }
+}
+
+impl<'a> Clone for Box<dyn CloneableEventIter<'a> + 'a> {
+    fn clone(&self) -> Self {
+        self.as_ref().box_clone()
+    }
+}
+
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PendingDelivery {
@@ -27,6 +72,7 @@ impl PartialOrd for PendingDelivery {
     }
 }
 
+#[derive(Clone)]
 struct VehicleState<'a> {
     vehicle: VehicleId,
     now: Time,
@@ -36,10 +82,10 @@ struct VehicleState<'a> {
     route: Vec<CallId>,
     time_stack: Vec<Time>,
     active: HashSet<CallId>,
-    pu_starts: Peekable<Box<dyn Iterator<Item = (Time, CallId)> + 'a>>,
-    pu_ends: Peekable<Box<dyn Iterator<Item = (Time, CallId)> + 'a>>,
-    de_starts: Peekable<Box<dyn Iterator<Item = (Time, CallId)> + 'a>>,
-    de_ends: Peekable<Box<dyn Iterator<Item = (Time, CallId)> + 'a>>,
+    pu_starts: Peekable<Box<dyn CloneableEventIter<'a> + 'a>>,
+    pu_ends: Peekable<Box<dyn CloneableEventIter<'a> + 'a>>,
+    de_starts: Peekable<Box<dyn CloneableEventIter<'a> + 'a>>,
+    de_ends: Peekable<Box<dyn CloneableEventIter<'a> + 'a>>,
     finished: bool,
 }
 
@@ -58,10 +104,10 @@ impl<'a> VehicleState<'a> {
             route: Vec::new(),
             time_stack: Vec::new(),
             active: HashSet::new(),
-            pu_starts: (Box::new(pickup_tree.start_events_from(now)) as Box<dyn Iterator<Item = (Time, CallId)>>).peekable(),
-            pu_ends: (Box::new(pickup_tree.end_events_from(now)) as Box<dyn Iterator<Item = (Time, CallId)>>).peekable(),
-            de_starts: (Box::new(delivery_tree.start_events_from(now)) as Box<dyn Iterator<Item = (Time, CallId)>>).peekable(),
-            de_ends: (Box::new(delivery_tree.end_events_from(now)) as Box<dyn Iterator<Item = (Time, CallId)>>).peekable(),
+            pu_starts: (Box::new(pickup_tree.start_events_from(now)) as Box<dyn CloneableEventIter<'a>>).peekable(),
+            pu_ends: (Box::new(pickup_tree.end_events_from(now)) as Box<dyn CloneableEventIter<'a>>).peekable(),
+            de_starts: (Box::new(delivery_tree.start_events_from(now)) as Box<dyn CloneableEventIter<'a>>).peekable(),
+            de_ends: (Box::new(delivery_tree.end_events_from(now)) as Box<dyn CloneableEventIter<'a>>).peekable(),
             finished: false,
         }
     }
@@ -102,29 +148,44 @@ impl<'a> VehicleState<'a> {
         }
     }
     
-    fn extend_one(
-        &mut self,
-        problem: &Problem,
-        global_pool: &mut HashSet<CallId>
-    ) -> bool {
-        self.advance_active();
-
-        let last_node = self.route.last().map(|&c| {
+    /// Returns the node the vehicle is currently at: the destination/origin of
+    /// its last visited call, or its home node if it hasn't moved yet.
+    fn current_node(&self, problem: &Problem) -> crate::types::NodeId {
+        self.route.last().map(|&c| {
             if c.is_pickup() {
                 problem.origin_node(c)
             } else {
                 problem.destination_node(c)
             }
-        }).unwrap_or(problem.get_vehicle_home_node(self.vehicle));
-        
+        }).unwrap_or(problem.get_vehicle_home_node(self.vehicle))
+    }
+
+    /// Enumerates every feasible pickup/delivery from `self.active` that
+    /// could legally be visited next, honoring `problem.locks`: a call
+    /// locked to another vehicle, or whose sequence-lock predecessor isn't
+    /// routed yet, never makes it into the list. Does not include the
+    /// forced-delivery fallback (see `forced_delivery`), which is only
+    /// consulted once this list comes back empty. Shared by the greedy
+    /// (`extend_one`) and beam-search (`beam_search_calls`) constructors so
+    /// both see identical feasibility checks.
+    fn candidates(&mut self, problem: &Problem, global_pool: &HashSet<CallId>) -> Vec<CallId> {
+        self.advance_active();
+
+        let last_node = self.current_node(problem);
         let earliest_pd_opt = self.pending_deliveries.iter().next().cloned();
-        
+
         let mut cands = Vec::new();
         for &c in self.active.iter() {
             if c.is_pickup() {
                 if !global_pool.contains(&c) {
                     continue;
                 }
+                if !problem.locks.is_vehicle_allowed(c, self.vehicle) {
+                    continue;
+                }
+                if problem.locks.sequence_violation(&self.route, c, self.route.len()) {
+                    continue;
+                }
                 if i32::from(self.load + problem.cargo_size(c))
                     > problem.get_vehicle_capacity(self.vehicle) {
                     continue;
@@ -138,7 +199,7 @@ impl<'a> VehicleState<'a> {
                 }
                 // Simulate the new state after inserting this pickup
                 let new_time = arrival + problem.service_time(self.vehicle, c);
-                
+
                 // If there is an earliest pending delivery, ensure that after this insertion it remains feasible
                 if let Some(ref pd) = earliest_pd_opt {
                     let pd_tw = problem.delivery_time_window(pd.call);
@@ -155,6 +216,9 @@ impl<'a> VehicleState<'a> {
                 if !self.pending_contains(&c, problem) {
                     continue;
                 }
+                if problem.locks.sequence_violation(&self.route, c, self.route.len()) {
+                    continue;
+                }
                 let node = problem.destination_node(c);
                 let tw = problem.delivery_time_window(c);
                 let travel = problem.get_travel_time(self.vehicle, last_node, node);
@@ -166,33 +230,33 @@ impl<'a> VehicleState<'a> {
             }
         }
 
-        // If no candidate is feasible and there is a pending delivery, force insertion
-        if cands.is_empty() {
-            if let Some(pd) = earliest_pd_opt {
-                let forced = pd.call; // this is a delivery call.
-                let node = problem.destination_node(forced);
-                let tw = problem.delivery_time_window(forced);
-                let travel = problem.get_travel_time(self.vehicle, last_node, node);
-                let arrival = (self.now + travel).max(*tw.start());
-                if arrival > *tw.end() {
-                    self.finished = true;
-                    return false;
-                }
-                self.time_stack.push(self.now);
-                self.route.push(forced);
-                self.remove_pending_delivery(forced, problem);
-                self.now = arrival + problem.service_time(self.vehicle, forced);
-                return true;
-            } else {
-                self.finished = true;
-                return false;
-            }
-        }
+        cands
+    }
 
-        // Otherwise, choose one candidate
-        let choice = cands[0];
+    /// The single delivery that must be forced next when `candidates` comes
+    /// back empty, to keep the earliest pending deadline reachable. `None`
+    /// if forcing it would itself miss its window, meaning the route is
+    /// done.
+    fn forced_delivery(&self, problem: &Problem) -> Option<CallId> {
+        let last_node = self.current_node(problem);
+        let pd = self.pending_deliveries.iter().next()?;
+        let forced = pd.call; // this is a delivery call.
+        let node = problem.destination_node(forced);
+        let tw = problem.delivery_time_window(forced);
+        let travel = problem.get_travel_time(self.vehicle, last_node, node);
+        let arrival = (self.now + travel).max(*tw.start());
+        (arrival <= *tw.end()).then_some(forced)
+    }
+
+    /// Commits to visiting `choice` next: updates load, pending deliveries,
+    /// the active set and `global_pool`, and advances `now` past the arrival
+    /// and service of `choice`. Assumes `choice` came out of `candidates`
+    /// (or is otherwise known feasible) and performs no further checks.
+    /// Returns the travel cost of the leg just taken, for callers that want
+    /// to track an accumulated cost (e.g. the beam-search constructor).
+    fn commit(&mut self, problem: &Problem, global_pool: &mut HashSet<CallId>, choice: CallId) -> Cost {
+        let last_node = self.current_node(problem);
 
-        // Record the current time.
         self.time_stack.push(self.now);
         self.route.push(choice);
         if choice.is_pickup() {
@@ -218,25 +282,300 @@ impl<'a> VehicleState<'a> {
             problem.delivery_time_window(choice)
         };
         let travel = problem.get_travel_time(self.vehicle, last_node, node);
+        let travel_cost = problem.get_travel_cost(self.vehicle, last_node, node);
         let arrival = (self.now + travel).max(*tw.start());
         self.now = arrival + problem.service_time(self.vehicle, choice);
 
-        true
+        travel_cost
+    }
+
+    /// Commits to the forced delivery returned by `forced_delivery`. Unlike
+    /// `commit`, this does not touch `load`: the original greedy fallback
+    /// never released it either, so this keeps `extend_one`'s behavior
+    /// unchanged.
+    fn commit_forced(&mut self, problem: &Problem, forced: CallId) {
+        let last_node = self.current_node(problem);
+        let node = problem.destination_node(forced);
+        let tw = problem.delivery_time_window(forced);
+        let travel = problem.get_travel_time(self.vehicle, last_node, node);
+        let arrival = (self.now + travel).max(*tw.start());
+
+        self.time_stack.push(self.now);
+        self.route.push(forced);
+        self.remove_pending_delivery(forced, problem);
+        self.now = arrival + problem.service_time(self.vehicle, forced);
+    }
+
+    /// Blends detour, urgency and pending-delivery pull into a single
+    /// positive roulette weight for candidate `c`, per `CandidateWeightParams`.
+    /// Recomputes the same arrival/window terms `candidates` used to decide
+    /// `c` was feasible, since the weight needs those intermediate values
+    /// too and `candidates` doesn't keep them around.
+    fn candidate_weight(&self, problem: &Problem, c: CallId, params: CandidateWeightParams) -> f32 {
+        let last_node = self.current_node(problem);
+
+        let (node, tw) = if c.is_pickup() {
+            (problem.origin_node(c), problem.pickup_time_window(c))
+        } else {
+            (problem.destination_node(c), problem.delivery_time_window(c))
+        };
+        let travel = problem.get_travel_time(self.vehicle, last_node, node);
+        let arrival = (self.now + travel).max(*tw.start());
+
+        let detour = travel.max(1) as f32;
+        let slack = (*tw.end() - arrival).max(0) as f32;
+        let urgency = 1.0 / (slack + 1.0);
+
+        let pull = if c.is_pickup() {
+            self.pending_deliveries.iter().next().map_or(0.0, |pd| {
+                let pd_tw = problem.delivery_time_window(pd.call);
+                let pd_node = problem.destination_node(pd.call);
+
+                let baseline_travel = problem.get_travel_time(self.vehicle, last_node, pd_node);
+                let baseline_arrival = (self.now + baseline_travel).max(*pd_tw.start());
+                let baseline_slack = (*pd_tw.end() - baseline_arrival) as f32;
+
+                let new_time = arrival + problem.service_time(self.vehicle, c);
+                let travel_pd = problem.get_travel_time(self.vehicle, node, pd_node);
+                let effective_arrival = (new_time + travel_pd).max(*pd_tw.start());
+                let effective_slack = (*pd_tw.end() - effective_arrival) as f32;
+
+                effective_slack - baseline_slack
+            })
+        } else {
+            0.0
+        };
+
+        (params.alpha / detour + params.beta * urgency + params.gamma * pull).max(0.01)
+    }
+
+    fn extend_one(
+        &mut self,
+        problem: &Problem,
+        global_pool: &mut HashSet<CallId>,
+        weight_params: CandidateWeightParams,
+        rng: &mut impl Rng,
+    ) -> bool {
+        let cands = self.candidates(problem, global_pool);
+
+        if !cands.is_empty() {
+            let weights: Vec<f32> = cands
+                .iter()
+                .map(|&c| self.candidate_weight(problem, c, weight_params))
+                .collect();
+            let dist = WeightedIndex::new(&weights).unwrap();
+            let choice = cands[dist.sample(rng)];
+            self.commit(problem, global_pool, choice);
+            return true;
+        }
+
+        if let Some(forced) = self.forced_delivery(problem) {
+            self.commit_forced(problem, forced);
+            return true;
+        }
+
+        self.finished = true;
+        false
     }
 }
 
-pub fn weighted_random_calls(problem: &Problem) -> Vec<Vec<CallId>> {
+pub fn weighted_random_calls(problem: &Problem, weight_params: CandidateWeightParams) -> Vec<Vec<CallId>> {
     let mut builders: Vec<VehicleState> = (1..=problem.n_vehicles().get())
         .map(|i| VehicleState::new(problem, VehicleId::new(i).unwrap()))
         .collect();
     let mut global_pool: HashSet<CallId> = problem.all_calls().collect();
-    
+    let mut rng = rand::rng();
+
     while !global_pool.is_empty() && builders.iter().any(|b| !b.finished) {
         for b in &mut builders {
             if !b.finished {
-                let _ = b.extend_one(problem, &mut global_pool);
+                let _ = b.extend_one(problem, &mut global_pool, weight_params, &mut rng);
             }
         }
     }
     builders.into_iter().map(|b| b.route).collect()
 }
+
+/// One partial construction: every vehicle's in-progress state, the pool of
+/// pickups not yet claimed by anyone, and the travel cost spent getting
+/// here. Forked into several successors at each beam-search step.
+#[derive(Clone)]
+struct BeamState<'a> {
+    builders: Vec<VehicleState<'a>>,
+    global_pool: HashSet<CallId>,
+    cost_so_far: Cost,
+}
+
+impl<'a> BeamState<'a> {
+    /// Mirrors `weighted_random_calls`'s own stop condition: done once the
+    /// pool is drained or every vehicle has given up.
+    fn finished(&self) -> bool {
+        self.global_pool.is_empty() || self.builders.iter().all(|b| b.finished)
+    }
+
+    /// A cheap, optimistic lower bound on the cost still needed to finish:
+    /// for each vehicle not yet finished, the cost of the single direct leg
+    /// to its most urgent pending delivery (which must be driven at least
+    /// once regardless of what route the vehicle ends up taking there),
+    /// plus, for every call still sitting unclaimed in `global_pool`, its
+    /// `cheapest_leg_costs` entry — the cheapest compatible vehicle's direct
+    /// pickup-to-delivery leg, which some vehicle must drive at least once
+    /// to deliver that call. Never overestimates, at the cost of being a
+    /// loose bound early in construction.
+    fn optimistic_remaining_cost(&self, problem: &Problem, cheapest_leg_costs: &HashMap<CallId, Cost>) -> Cost {
+        let pending_legs: Cost = self.builders
+            .iter()
+            .filter(|b| !b.finished)
+            .filter_map(|b| {
+                let pd = b.pending_deliveries.iter().next()?;
+                let from = b.current_node(problem);
+                let to = problem.destination_node(pd.call);
+                Some(problem.get_travel_cost(b.vehicle, from, to))
+            })
+            .sum();
+
+        let unclaimed_legs: Cost = self.global_pool
+            .iter()
+            .map(|&call| cheapest_leg_costs.get(&call).copied().unwrap_or(0))
+            .sum();
+
+        pending_legs + unclaimed_legs
+    }
+}
+
+/// For every call, the cheapest direct pickup-to-delivery leg cost among its
+/// compatible vehicles: a lower bound on the cost any vehicle assigned that
+/// call must eventually pay, computed once from the cost matrices rather
+/// than re-derived per beam-search state.
+fn cheapest_leg_costs(problem: &Problem) -> HashMap<CallId, Cost> {
+    problem.all_calls()
+        .map(|call| {
+            let origin = problem.origin_node(call);
+            let destination = problem.destination_node(call);
+            let cost = problem.get_compatible_vehicles(call)
+                .iter()
+                .map(|&vehicle| problem.get_travel_cost(vehicle, origin, destination))
+                .min()
+                .unwrap_or(0);
+            (call, cost)
+        })
+        .collect()
+}
+
+/// Wraps a `BeamState` with its score so a bounded `BinaryHeap` can rank
+/// states by estimate alone without requiring `BeamState` itself to be
+/// `Ord` — the same pattern `PendingDelivery` uses above for its deadline.
+struct ScoredState<'a> {
+    estimate: Cost,
+    state: BeamState<'a>,
+}
+
+impl<'a> PartialEq for ScoredState<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<'a> Eq for ScoredState<'a> {}
+
+impl<'a> PartialOrd for ScoredState<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for ScoredState<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimate.cmp(&other.estimate)
+    }
+}
+
+/// Scores `state` and pushes it onto `heap`, evicting the current worst
+/// (highest-estimate) entry whenever that pushes the heap past `beam_width`.
+fn push_bounded<'a>(
+    heap: &mut BinaryHeap<ScoredState<'a>>,
+    beam_width: usize,
+    problem: &Problem,
+    cheapest_leg_costs: &HashMap<CallId, Cost>,
+    state: BeamState<'a>,
+) {
+    let estimate = state.cost_so_far.saturating_add(state.optimistic_remaining_cost(problem, cheapest_leg_costs));
+    heap.push(ScoredState { estimate, state });
+    if heap.len() > beam_width {
+        heap.pop();
+    }
+}
+
+/// Beam-search counterpart to `weighted_random_calls`. Where `extend_one`
+/// commits to one roulette-sampled candidate and throws the rest of the candidate set
+/// away, this keeps the best `beam_width` partial assignments alive at
+/// once. At each step, every surviving state forks one successor per
+/// feasible candidate of its next unfinished vehicle (reusing `candidates`
+/// for the exact same capacity/time-window checks `extend_one` runs), each
+/// successor is scored by `cost_so_far + optimistic_remaining_cost`, and a
+/// bounded `BinaryHeap` keeps only the best `beam_width` of them, giving
+/// `O(beam * candidates * log beam)` work per step. Continues until every
+/// surviving state is finished, then returns the routes of whichever state
+/// ended with the lowest actual cost.
+///
+/// `beam_width == 1` always keeps just the single successor with the lowest
+/// estimate, giving a deterministic greedy construction. It does not
+/// reproduce `weighted_random_calls`: that one samples candidates by weight
+/// and takes one step per vehicle in turn, while this one always extends the
+/// first unfinished vehicle.
+pub fn beam_search_calls(problem: &Problem, beam_width: usize) -> Vec<Vec<CallId>> {
+    let beam_width = beam_width.max(1);
+
+    let builders: Vec<VehicleState> = (1..=problem.n_vehicles.get())
+        .map(|i| VehicleState::new(problem, VehicleId::new(i).unwrap()))
+        .collect();
+    let global_pool: HashSet<CallId> = problem.all_calls().collect();
+    let cheapest_leg_costs = cheapest_leg_costs(problem);
+
+    let mut beam = vec![BeamState { builders, global_pool, cost_so_far: 0 }];
+
+    while beam.iter().any(|s| !s.finished()) {
+        let mut heap: BinaryHeap<ScoredState> = BinaryHeap::with_capacity(beam_width + 1);
+
+        for state in &beam {
+            if state.finished() {
+                push_bounded(&mut heap, beam_width, problem, &cheapest_leg_costs, state.clone());
+                continue;
+            }
+
+            let Some(vehicle_idx) = state.builders.iter().position(|b| !b.finished) else {
+                push_bounded(&mut heap, beam_width, problem, &cheapest_leg_costs, state.clone());
+                continue;
+            };
+
+            let mut probe = state.builders[vehicle_idx].clone();
+            let cands = probe.candidates(problem, &state.global_pool);
+
+            if !cands.is_empty() {
+                for &choice in &cands {
+                    let mut next = state.clone();
+                    let leg_cost = next.builders[vehicle_idx].commit(problem, &mut next.global_pool, choice);
+                    next.cost_so_far = next.cost_so_far.saturating_add(leg_cost);
+                    push_bounded(&mut heap, beam_width, problem, &cheapest_leg_costs, next);
+                }
+            } else if let Some(forced) = probe.forced_delivery(problem) {
+                let mut next = state.clone();
+                next.builders[vehicle_idx].commit_forced(problem, forced);
+                push_bounded(&mut heap, beam_width, problem, &cheapest_leg_costs, next);
+            } else {
+                let mut next = state.clone();
+                next.builders[vehicle_idx].finished = true;
+                push_bounded(&mut heap, beam_width, problem, &cheapest_leg_costs, next);
+            }
+        }
+
+        beam = heap.into_sorted_vec().into_iter().map(|s| s.state).collect();
+    }
+
+    let best = beam
+        .into_iter()
+        .min_by_key(|s| s.cost_so_far)
+        .expect("beam search always keeps at least one state alive");
+
+    best.builders.into_iter().map(|b| b.route).collect()
+}
@@ -1,7 +1,7 @@
 use rand::prelude::*;
 use crate::problem::Problem;
 use crate::solution::Solution;
-use crate::types::{CallId, VehicleId};
+use crate::types::{CallId, Cost, VehicleId};
 
 
 pub fn random_placement_one(solution: &mut Solution, problem: &Problem, calls: Vec<CallId>) -> (usize, usize) {
@@ -23,7 +23,7 @@ pub fn random_placement_one(solution: &mut Solution, problem: &Problem, calls: V
         } else {
             // Reinstate the call into its original location if available.
             if let (Some(vehicle), Some(pickup), Some(delivery)) = removed {
-                if let Err(err) = solution.insert_call(vehicle, call, pickup, delivery) {
+                if let Err(err) = solution.insert_call(problem, vehicle, call, pickup, delivery) {
                     eprintln!(
                         "Failed to reinsert call {:?} into vehicle {:?} at positions ({}, {}): {:?}",
                         call, vehicle, pickup, delivery, err
@@ -63,6 +63,287 @@ pub fn random_placement_all(
     (evaluated, infeasible)
 }
 
+/// Reinserts `calls` using regret-`k` insertion, scoped to exactly the given
+/// calls rather than every unassigned call in `solution` (unlike
+/// `operators::ruin_recreate::regret_k_recreate`, which this otherwise
+/// mirrors): at each step, rank every still-unplaced call by the regret of
+/// delaying it — the gap between its cheapest feasible vehicle and the next
+/// `k - 1` cheapest — and commit the call with the largest regret at its own
+/// cheapest feasible position. A call feasible in fewer than `k` vehicles is
+/// treated as having near-infinite regret so hard-to-place calls go first; a
+/// call with no feasible insertion anywhere is left unassigned.
+pub fn regret_k_insertion(
+    solution: &mut Solution,
+    problem: &Problem,
+    calls: Vec<CallId>,
+    k: usize,
+) -> (usize, usize) {
+    let mut evaluated = 0;
+    let mut infeasible = 0;
+
+    // Remove all specified calls from the current solution.
+    for &call in &calls {
+        let _ = solution.remove_call(call);
+    }
+
+    let mut remaining = calls;
+
+    while !remaining.is_empty() {
+        let mut best_call: Option<(usize, VehicleId, usize, usize, Cost, f32)> = None;
+
+        for (list_idx, &call) in remaining.iter().enumerate() {
+            let mut per_vehicle_best: Vec<(Cost, VehicleId, usize, usize)> = Vec::new();
+
+            for &vehicle in problem.get_compatible_vehicles(call.pickup()) {
+                let (_, capacity_result) = solution.find_spare_capacity_in_vehicle(problem, call, vehicle);
+                if capacity_result.is_none() {
+                    continue;
+                }
+                let capacity_result = capacity_result.clone();
+
+                let candidates: Vec<(usize, usize)> = solution
+                    .get_feasible_insertions(problem, call, vehicle, &capacity_result)
+                    .collect();
+
+                let mut cheapest: Option<(Cost, usize, usize)> = None;
+                for (pickup_idx, delivery_idx) in candidates {
+                    evaluated += 1;
+
+                    if solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx).is_err() {
+                        continue;
+                    }
+
+                    if solution.feasible(problem).is_err() {
+                        let _ = solution.remove_call(call);
+                        infeasible += 1;
+                        continue;
+                    }
+
+                    let cost = solution.cost(problem);
+                    let _ = solution.remove_call(call);
+
+                    if cheapest.map_or(true, |(c, _, _)| cost < c) {
+                        cheapest = Some((cost, pickup_idx, delivery_idx));
+                    }
+                }
+
+                if let Some((cost, pickup_idx, delivery_idx)) = cheapest {
+                    per_vehicle_best.push((cost, vehicle, pickup_idx, delivery_idx));
+                }
+            }
+
+            if per_vehicle_best.is_empty() {
+                continue;
+            }
+
+            per_vehicle_best.sort_by_key(|&(cost, _, _, _)| cost);
+
+            let (best_cost, best_vehicle, best_pickup, best_delivery) = per_vehicle_best[0];
+
+            // A call feasible in fewer than `k` vehicles contributes the
+            // largest possible regret so hard-to-place calls are prioritized.
+            let regret: f32 = (1..k)
+                .map(|i| {
+                    per_vehicle_best
+                        .get(i)
+                        .map(|&(cost, _, _, _)| (cost - best_cost) as f32)
+                        .unwrap_or(f32::MAX / (k as f32))
+                })
+                .sum();
+
+            // Ties (e.g. every call has regret 0.0 at `k == 1`) break on cost,
+            // so `regret_insertion(..., greediness: 1.0)` degenerates into
+            // always inserting the globally cheapest call next.
+            if best_call.as_ref().map_or(true, |&(_, _, _, _, current_best_cost, r)| {
+                regret > r || (regret == r && best_cost < current_best_cost)
+            }) {
+                best_call = Some((list_idx, best_vehicle, best_pickup, best_delivery, best_cost, regret));
+            }
+        }
+
+        match best_call {
+            Some((list_idx, vehicle, pickup_idx, delivery_idx, _, _)) => {
+                let call = remaining.remove(list_idx);
+                let _ = solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx);
+            }
+            None => {
+                // No remaining call has a feasible insertion anywhere; leave
+                // them all unassigned.
+                infeasible += remaining.len();
+                break;
+            }
+        }
+    }
+
+    (evaluated, infeasible)
+}
+
+/// Largest `k` `regret_insertion` will use, at `greediness == 0.0` (full
+/// regret-`k`). `greediness == 1.0` collapses to `k == 1`, i.e. pure-greedy
+/// insertion of whichever call is cheapest to place right now.
+const REGRET_INSERTION_MAX_K: usize = 5;
+
+/// Like `regret_k_insertion`, but exposes a single `[0, 1]` knob instead of a
+/// raw `k`: `greediness` blends between pure-greedy insertion (`1.0`, `k ==
+/// 1`, always insert the globally cheapest call next) and full regret-`k`
+/// (`0.0`, `k == REGRET_INSERTION_MAX_K`, prioritize the call that would hurt
+/// most to delay). `k = 1 + round((1.0 - greediness) * (REGRET_INSERTION_MAX_K - 1))`.
+pub fn regret_insertion(
+    solution: &mut Solution,
+    problem: &Problem,
+    calls: Vec<CallId>,
+    greediness: f32,
+) -> (usize, usize) {
+    let greediness = greediness.clamp(0.0, 1.0);
+    let k = 1 + ((1.0 - greediness) * (REGRET_INSERTION_MAX_K - 1) as f32).round() as usize;
+
+    regret_k_insertion(solution, problem, calls, k)
+}
+
+/// Orders `calls` (all in pickup form) by a greedy nearest-neighbor walk over
+/// their origin nodes in `vehicle`'s travel-time table, starting from the
+/// first call in `calls`. Mirrors `problem::clustering`'s own
+/// nearest-neighbor ordering, recomputed here rather than shared since that
+/// one is private to its module and walks a whole cluster, while this one
+/// only ever walks the (small) still-unassigned subset of a cluster.
+fn nearest_neighbor_order(problem: &Problem, vehicle: VehicleId, calls: &[CallId]) -> Vec<CallId> {
+    let mut remaining = calls.to_vec();
+    let first = remaining.remove(0);
+    let mut current = problem.origin_node(first);
+    let mut ordered = vec![first];
+
+    while !remaining.is_empty() {
+        let (idx, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (i, problem.get_travel_time(vehicle, current, problem.origin_node(c))))
+            .min_by_key(|&(_, time)| time)
+            .expect("remaining is non-empty");
+
+        let next = remaining.remove(idx);
+        current = problem.origin_node(next);
+        ordered.push(next);
+    }
+
+    ordered
+}
+
+/// Inserts `ordered` into `vehicle`'s route at `base` as one nested block:
+/// pickups in `ordered`'s order, deliveries immediately following in the
+/// reverse order (so the first call picked up is the last delivered), with
+/// no other stop interleaved. Indices are derived much as
+/// `insert_nested_sequence` derives them for an expanded cluster -- a call's
+/// pickup index is `base` plus twice the number of already-committed calls
+/// that sit entirely before it, and its delivery index follows immediately
+/// after the committed calls nested inside it -- just committed in
+/// delivery-position order instead of being handed a precomputed sequence.
+/// Returns `false` the moment one member can't be placed, e.g. a lock
+/// violation; members already inserted stay in the route for the caller to
+/// remove.
+fn insert_cluster_block(solution: &mut Solution, problem: &Problem, vehicle: VehicleId, ordered: &[CallId], base: usize) -> bool {
+    let n = ordered.len();
+    let mut commit_order: Vec<usize> = (0..n).collect();
+    commit_order.sort_by_key(|&i| n - 1 - i); // delivery position ascending == pickup position descending
+
+    let mut committed: Vec<usize> = Vec::with_capacity(n);
+
+    for pickup_pos in commit_order {
+        let before = committed.iter().filter(|&&p| p < pickup_pos).count();
+        let nested = committed.len() - before;
+
+        let pickup_idx = base + 2 * before;
+        let delivery_idx = pickup_idx + 2 * nested;
+
+        if solution.insert_call(problem, vehicle, ordered[pickup_pos], pickup_idx, delivery_idx).is_err() {
+            return false;
+        }
+
+        committed.push(pickup_pos);
+    }
+
+    true
+}
+
+/// Reinserts every currently-unassigned member of `cluster` (see
+/// `Problem::call_clusters`, a precomputed grouping of mutually close calls)
+/// into a single vehicle as one nested block, instead of placing each member
+/// independently -- avoiding the wasted travel of visiting the same
+/// neighborhood once per call. Tries every vehicle compatible with every
+/// unassigned member at every contiguous route position, keeping the
+/// cheapest block placement that passes `Solution::feasible` (the same
+/// trial-insert/feasible/cost/revert idiom as `cheapest_insertion_for_call`,
+/// since a block's feasibility isn't cheaply decomposable the way a single
+/// call's is in `feasibility::is_insertion_time_feasible`; `feasible`'s full
+/// route simulation accounts for the block's aggregate added time against
+/// slack exactly, rather than approximating it). Members already assigned
+/// elsewhere are left untouched. Service time is still charged per call --
+/// amortizing it into one shared "parking" charge at the cluster's location
+/// would need a change to how `Route::simulate` charges service time, not
+/// just to insertion, so the savings this operator finds come from shared
+/// travel detour only. A cluster with fewer than two unassigned members
+/// falls back to `regret_k_insertion` for whichever member remains.
+pub fn cluster_insertion(solution: &mut Solution, problem: &Problem, cluster: &[CallId]) -> (usize, usize) {
+    let unassigned: Vec<CallId> = cluster
+        .iter()
+        .map(|call| call.pickup())
+        .filter(|&call| solution.is_unassigned(call))
+        .collect();
+
+    if unassigned.len() < 2 {
+        return regret_k_insertion(solution, problem, unassigned, 1);
+    }
+
+    let mut evaluated = 0;
+    let mut infeasible = 0;
+
+    let compatible_vehicles: Vec<VehicleId> = problem
+        .get_compatible_vehicles(unassigned[0])
+        .iter()
+        .copied()
+        .filter(|v| unassigned.iter().all(|&call| problem.get_compatible_vehicles(call).contains(v)))
+        .collect();
+
+    let mut best: Option<(Cost, VehicleId, usize)> = None;
+
+    for vehicle in compatible_vehicles {
+        let ordered = nearest_neighbor_order(problem, vehicle, &unassigned);
+        let route_len = solution.routes()[vehicle.index()].route().len();
+
+        for base in 0..=route_len {
+            evaluated += 1;
+
+            let cost = if insert_cluster_block(solution, problem, vehicle, &ordered, base) && solution.feasible(problem).is_ok() {
+                Some(solution.cost(problem))
+            } else {
+                None
+            };
+
+            for &call in &ordered {
+                let _ = solution.remove_call(call);
+            }
+
+            match cost {
+                Some(cost) if best.map_or(true, |(best_cost, _, _)| cost < best_cost) => best = Some((cost, vehicle, base)),
+                None => infeasible += 1,
+                _ => {}
+            }
+        }
+    }
+
+    match best {
+        Some((_, vehicle, base)) => {
+            let ordered = nearest_neighbor_order(problem, vehicle, &unassigned);
+            assert!(
+                insert_cluster_block(solution, problem, vehicle, &ordered, base),
+                "block placement already validated as feasible"
+            );
+        }
+        None => infeasible += unassigned.len(),
+    }
+
+    (evaluated, infeasible)
+}
+
 /// Attempts to insert the given call into a random feasible insertion point,
 /// skipping the original location given by `removed` (if any).
 fn attempt_insert_call(
@@ -100,7 +381,7 @@ fn attempt_insert_call(
                     }
                 }
 
-                if solution.insert_call(vehicle, call, pickup_idx, delivery_idx).is_ok() {
+                if solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx).is_ok() {
                     if solution.feasible(problem).is_err() {
                         let _ = solution.remove_call(call);
                         *infeasible += 1;
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use crate::problem::Problem;
+use crate::solution::Solution;
+use crate::types::{CallId, Capacity, VehicleId};
+
+/// Max assigned-call count, in a single vehicle's route, `permutation_polish`
+/// will exhaustively search; routes above this size are skipped so the
+/// backtracking search stays bounded instead of blowing up on real-sized
+/// routes.
+pub const PERMUTATION_POLISH_MAX_CALLS: usize = 6;
+
+/// Exhaustively searches every precedence-respecting (a call's pickup always
+/// precedes its own delivery), capacity-feasible ordering of `vehicle`'s
+/// route when it holds at most `k` calls, keeping the minimum-cost feasible
+/// ordering found. Skips vehicles above `k` calls to bound the factorial
+/// blow-up. Meant as a cheap exact-within-route polish step `ALNS::run` can
+/// invoke on the vehicle(s) touched by the last destroy/repair. Returns
+/// whether a strictly cheaper ordering than the route's current one was
+/// installed.
+pub fn permutation_polish(solution: &mut Solution, problem: &Problem, vehicle: VehicleId, k: usize) -> bool {
+    let route_calls = solution.route(vehicle);
+    if route_calls.is_empty() {
+        return false;
+    }
+
+    let mut calls: Vec<CallId> = Vec::new();
+    for &stop in &route_calls {
+        let pickup = stop.pickup();
+        if !calls.contains(&pickup) {
+            calls.push(pickup);
+        }
+    }
+
+    if calls.len() > k {
+        return false;
+    }
+
+    let capacity = problem.get_vehicle(vehicle).capacity;
+    let cargo: Vec<Capacity> = calls.iter().map(|&call| problem.cargo_size(call) as Capacity).collect();
+
+    let mut candidates = Vec::new();
+    let mut current = Vec::with_capacity(route_calls.len());
+    let mut picked_up = vec![false; calls.len()];
+    let mut delivered = vec![false; calls.len()];
+    enumerate_orders(&calls, &cargo, capacity, &mut current, &mut picked_up, &mut delivered, 0, &mut candidates);
+
+    let baseline_cost = solution.cost(problem);
+
+    let mut best_order = route_calls;
+    let mut best_cost = baseline_cost;
+
+    for &call in &calls {
+        let _ = solution.remove_call(call);
+    }
+
+    for order in candidates {
+        realize_order(solution, problem, vehicle, &order);
+
+        if solution.feasible(problem).is_ok() {
+            let cost = solution.cost(problem);
+            if cost < best_cost {
+                best_cost = cost;
+                best_order = order.clone();
+            }
+        }
+
+        for &call in &calls {
+            let _ = solution.remove_call(call);
+        }
+    }
+
+    realize_order(solution, problem, vehicle, &best_order);
+
+    best_cost < baseline_cost
+}
+
+/// Depth-first generation of every precedence-respecting interleaving of
+/// `calls`' pickups and deliveries, pruning any partial ordering whose
+/// running load would exceed `capacity` (the legacy weight dimension only;
+/// `permutation_polish` relies on `Solution::feasible` to catch any
+/// additional-dimension or time-window violation once a candidate is
+/// materialized).
+#[allow(clippy::too_many_arguments)]
+fn enumerate_orders(
+    calls: &[CallId],
+    cargo: &[Capacity],
+    capacity: Capacity,
+    current: &mut Vec<CallId>,
+    picked_up: &mut [bool],
+    delivered: &mut [bool],
+    load: Capacity,
+    out: &mut Vec<Vec<CallId>>,
+) {
+    if current.len() == calls.len() * 2 {
+        out.push(current.clone());
+        return;
+    }
+
+    for i in 0..calls.len() {
+        if !picked_up[i] {
+            let new_load = load + cargo[i];
+            if new_load <= capacity {
+                picked_up[i] = true;
+                current.push(calls[i].pickup());
+                enumerate_orders(calls, cargo, capacity, current, picked_up, delivered, new_load, out);
+                current.pop();
+                picked_up[i] = false;
+            }
+        } else if !delivered[i] {
+            delivered[i] = true;
+            current.push(calls[i].delivery());
+            enumerate_orders(calls, cargo, capacity, current, picked_up, delivered, load - cargo[i], out);
+            current.pop();
+            delivered[i] = false;
+        }
+    }
+}
+
+/// Rebuilds `vehicle`'s route (assumed empty) to match `order` by inserting
+/// one call at a time, as each call's delivery is reached in `order`,
+/// with `pickup_idx`/`delivery_idx` computed as how many already-committed
+/// stops precede that target position. This realizes any precedence-valid
+/// interleaving through `Solution::insert_call`'s one-call-at-a-time API,
+/// including orderings where two calls' spans cross rather than nest (unlike
+/// `solution::insert_nested_sequence`, which only handles the nested case).
+fn realize_order(solution: &mut Solution, problem: &Problem, vehicle: VehicleId, order: &[CallId]) {
+    let mut committed_positions: Vec<usize> = Vec::with_capacity(order.len());
+    let mut pickup_target: HashMap<CallId, usize> = HashMap::with_capacity(order.len() / 2);
+
+    for (target_idx, &stop) in order.iter().enumerate() {
+        if stop.is_pickup() {
+            pickup_target.insert(stop, target_idx);
+            continue;
+        }
+
+        let call = stop.pickup();
+        let this_pickup_target = pickup_target[&call];
+
+        let pickup_idx = committed_positions.iter().filter(|&&p| p < this_pickup_target).count();
+        let delivery_idx = committed_positions.iter().filter(|&&p| p < target_idx).count();
+
+        solution
+            .insert_call(problem, vehicle, call, pickup_idx, delivery_idx)
+            .expect("permutation candidate respects precedence and capacity, so reinsertion must succeed");
+
+        committed_positions.push(this_pickup_target);
+        committed_positions.push(target_idx);
+    }
+}
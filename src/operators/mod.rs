@@ -3,5 +3,7 @@ pub mod mutate;
 pub mod removal;
 pub mod construction;
 pub mod params;
+pub mod ruin_recreate;
+pub mod local_search;
 
 pub use self::mutate::{REMOVAL_OPERATORS, INSERTION_OPERATORS};
@@ -1,21 +1,47 @@
-use rand::{random_range, rng};
 use rand::distr::weighted::WeightedIndex;
 use rand::prelude::*;
+use rand::RngCore;
 
-use crate::operators::insertion::{random_placement_one, random_placement_all};
+use crate::operators::insertion::{random_placement_one, random_placement_all, regret_insertion};
 use crate::operators::params::RemovalParams;
 use crate::operators::removal::*;
 use crate::problem::Problem;
 use crate::solution::Solution;
 use crate::types::CallId;
 
-const REMOVAL_OPERATORS: [fn(&Solution, &RemovalParams) -> Vec<CallId>; 3] = [
+const REMOVAL_OPERATORS: [fn(&Solution, &Problem, &mut dyn RngCore, &RemovalParams) -> Vec<CallId>; 8] = [
     combined_cost,
     broken_vehicle,
-    global_waiting
+    global_waiting,
+    worst_calls,
+    shaw_removal,
+    detour_removal,
+    worst_job_removal,
+    cluster_removal,
 ];
 
-const WEIGHTS: [f64; 3] = [0.3, 0.5, 0.2];
+const WEIGHTS: [f64; 8] = [0.3, 0.5, 0.2, 0.3, 0.4, 0.4, 0.4, 0.4];
+
+/// Curried `regret_insertion` at a fixed greediness, so it fits
+/// `INSERTION_OPERATORS`'s uniform signature alongside `random_placement_all`.
+fn regret_insertion_greedy(solution: &mut Solution, problem: &Problem, calls: Vec<CallId>) -> (usize, usize) {
+    regret_insertion(solution, problem, calls, 1.0)
+}
+
+fn regret_insertion_balanced(solution: &mut Solution, problem: &Problem, calls: Vec<CallId>) -> (usize, usize) {
+    regret_insertion(solution, problem, calls, 0.5)
+}
+
+fn regret_insertion_full(solution: &mut Solution, problem: &Problem, calls: Vec<CallId>) -> (usize, usize) {
+    regret_insertion(solution, problem, calls, 0.0)
+}
+
+const INSERTION_OPERATORS: [fn(&mut Solution, &Problem, Vec<CallId>) -> (usize, usize); 4] = [
+    random_placement_all,
+    regret_insertion_greedy,
+    regret_insertion_balanced,
+    regret_insertion_full,
+];
 
 const PARAMS: RemovalParams = RemovalParams {
     selection_ratio: 0.5,
@@ -24,32 +50,142 @@ const PARAMS: RemovalParams = RemovalParams {
     assignment_bias: 0.5,
     min_removals: 1,
     max_removals: 7,
+    shaw_pickup_weight: 0.3,
+    shaw_delivery_weight: 0.25,
+    shaw_time_weight: 0.15,
+    shaw_load_weight: 0.1,
+    shaw_compatibility_weight: 0.1,
+    shaw_vehicle_weight: 0.1,
+    shaw_determinism: 6.0,
+    worst_exp: 4.0,
+    worst_skip: 2,
+    cluster_adjacency_threshold: 0.15,
 };
 
-pub fn roulette_wheel_tuned(solution: &mut Solution, problem: &Problem) -> (usize, usize) {
-    let mut thread_rng = rng();
+/// Reward granted to the removal operator chosen in a segment, mirroring the
+/// new-best / improved-incumbent / accepted-worse-move tiers used by `ALNS`.
+pub const PSI_NEW_BEST: f32 = 33.0;
+pub const PSI_IMPROVED_INCUMBENT: f32 = 9.0;
+pub const PSI_ACCEPTED_WORSE: f32 = 13.0;
+
+/// Reactive operator-selection weights for `roulette_wheel_tuned`, following the
+/// same segment/reward/reaction-factor scheme as `search::alns::ALNS`: each
+/// removal operator starts at its tuned weight, accumulates a score while it is
+/// used, and every `segment_len` iterations the weights drift towards how well
+/// each operator actually paid off.
+pub struct ReactiveRemovalWeights {
+    weights: Vec<f32>,
+    score: Vec<f32>,
+    uses: Vec<u32>,
+    segment_len: usize,
+    iter_in_segment: usize,
+    lambda: f32,
+}
+
+/// Outcome of a single `ReactiveRemovalWeights::choose_and_apply` call.
+pub struct RouletteOutcome {
+    pub evaluations: usize,
+    pub infeasible: usize,
+    pub operator_idx: usize,
+}
+
+impl ReactiveRemovalWeights {
+    pub fn new() -> Self {
+        Self::with_reaction_factor(0.1, 100)
+    }
+
+    pub fn with_reaction_factor(lambda: f32, segment_len: usize) -> Self {
+        ReactiveRemovalWeights {
+            // Seed with the existing tuned weights rather than starting uniform.
+            weights: WEIGHTS.iter().map(|&w| w as f32).collect(),
+            score: vec![0.0; WEIGHTS.len()],
+            uses: vec![0; WEIGHTS.len()],
+            segment_len,
+            iter_in_segment: 0,
+            lambda,
+        }
+    }
+
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Picks an operator proportional to its current weight, applies it, and
+    /// returns the evaluation counts alongside the chosen operator's index so
+    /// the caller can later report a reward via `reward`.
+    pub fn choose_and_apply(&mut self, solution: &mut Solution, problem: &Problem, rng: &mut dyn RngCore) -> RouletteOutcome {
+        let dist = WeightedIndex::new(&self.weights).unwrap();
+        let operator_idx = dist.sample(&mut *rng);
+        let selected_fn = REMOVAL_OPERATORS[operator_idx];
+
+        let calls = selected_fn(solution, problem, rng, &PARAMS);
+        let (evaluations, infeasible) = random_placement_all(solution, problem, calls);
+
+        self.uses[operator_idx] += 1;
+
+        RouletteOutcome {
+            evaluations,
+            infeasible,
+            operator_idx,
+        }
+    }
+
+    /// Records a reward (one of the `PSI_*` constants, or `0.0`) for the operator
+    /// chosen by the most recent `choose_and_apply`, updating weights once
+    /// `segment_len` iterations have accumulated.
+    pub fn reward(&mut self, operator_idx: usize, reward: f32) {
+        self.score[operator_idx] += reward;
+        self.iter_in_segment += 1;
+
+        if self.iter_in_segment >= self.segment_len {
+            self.update_weights();
+        }
+    }
+
+    fn update_weights(&mut self) {
+        for o in 0..self.weights.len() {
+            let average_score = self.score[o] / (self.uses[o].max(1) as f32);
+            self.weights[o] = self.weights[o] * (1.0 - self.lambda) + self.lambda * average_score;
+            self.score[o] = 0.0;
+            self.uses[o] = 0;
+        }
+        self.iter_in_segment = 0;
+    }
+}
+
+impl Default for ReactiveRemovalWeights {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+pub fn roulette_wheel_tuned(solution: &mut Solution, problem: &Problem, rng: &mut dyn RngCore) -> (usize, usize) {
     let dist = WeightedIndex::new(WEIGHTS).unwrap();
-    let selected_fn = REMOVAL_OPERATORS[dist.sample(&mut thread_rng)];
+    let selected_fn = REMOVAL_OPERATORS[dist.sample(&mut *rng)];
 
-    let calls = selected_fn(solution, &PARAMS);
+    let calls = selected_fn(solution, problem, rng, &PARAMS);
 
     random_placement_all(solution, problem, calls)
 }
 
-pub fn roulette_wheel_equal(solution: &mut Solution, problem: &Problem) -> (usize, usize) {
-    let calls = match random_range(0..3) {
-        0 => combined_cost(solution, &PARAMS),
-        1 => broken_vehicle(solution,  &PARAMS),
-        2 => global_waiting(solution, &PARAMS),
+pub fn roulette_wheel_equal(solution: &mut Solution, problem: &Problem, rng: &mut dyn RngCore) -> (usize, usize) {
+    let calls = match rng.random_range(0..8) {
+        0 => combined_cost(solution, problem, rng, &PARAMS),
+        1 => broken_vehicle(solution, problem, rng, &PARAMS),
+        2 => global_waiting(solution, problem, rng, &PARAMS),
+        3 => worst_calls(solution, problem, rng, &PARAMS),
+        4 => shaw_removal(solution, problem, rng, &PARAMS),
+        5 => detour_removal(solution, problem, rng, &PARAMS),
+        6 => worst_job_removal(solution, problem, rng, &PARAMS),
+        7 => cluster_removal(solution, problem, rng, &PARAMS),
         _ => unreachable!(),
     };
 
     random_placement_all(solution, problem, calls)
 }
 
-pub fn mutate(solution: &mut Solution, problem: &Problem) -> (usize, usize) {
-    let calls = random_calls(solution, 1);
+pub fn mutate(solution: &mut Solution, problem: &Problem, rng: &mut dyn RngCore) -> (usize, usize) {
+    let calls = random_calls(solution, rng, 1);
 
     random_placement_one(solution, problem, calls)
 }
@@ -5,7 +5,29 @@ pub struct RemovalParams {
     pub cost_bias: f32,        // Influence of cost in selection
     pub assignment_bias: f32,  // Preference for assigned/unassigned calls
     pub min_removals: usize,   // Minimum removals
-    pub max_removals: usize   // Maximum removals
+    pub max_removals: usize,  // Maximum removals
+
+    // Shaw (relatedness) removal weights: how much each term of the
+    // relatedness measure contributes to `operators::removal::shaw_removal`'s
+    // pick of the next related call to remove.
+    pub shaw_pickup_weight: f32,         // Weight of normalized pickup-node distance
+    pub shaw_delivery_weight: f32,       // Weight of normalized delivery-node distance
+    pub shaw_time_weight: f32,           // Weight of normalized pickup time-window start difference
+    pub shaw_load_weight: f32,           // Weight of normalized cargo-size difference
+    pub shaw_compatibility_weight: f32,  // Weight of compatible-vehicle-set distance
+    pub shaw_vehicle_weight: f32,        // Weight of the same-vehicle indicator
+    pub shaw_determinism: f32,           // Determinism exponent p (1.0 = uniform, higher = greedier)
+
+    // `operators::removal::worst_job_removal`'s biased draw over the
+    // cost-ranked call list, and how many spatial neighbours of each drawn
+    // call to remove alongside it.
+    pub worst_exp: f32,    // Determinism exponent (1.0 = uniform, higher = greedier toward the costliest calls)
+    pub worst_skip: usize, // Nearest neighbours removed together with each worst call drawn
+
+    /// Maximum normalized pickup-node distance (see `Problem::relatedness_terms`)
+    /// for two calls to count as neighbours in `operators::removal::cluster_removal`'s
+    /// flood fill.
+    pub cluster_adjacency_threshold: f32,
 }
 
 pub enum SamplingMethod {
@@ -1,15 +1,16 @@
 use std::cmp::{max, min};
-use std::collections::HashMap;
-use rand::{rng, Rng};
+use std::collections::{HashMap, HashSet, VecDeque};
 use rand::seq::index::sample;
 use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
 
+use crate::problem::Problem;
 use crate::solution::Solution;
-use crate::types::{CallId, Time};
+use crate::types::{CallId, Cost, Time, VehicleId};
 
 use super::params::RemovalParams;
 
-pub(crate) fn global_waiting(solution: &Solution, params: &RemovalParams) -> Vec<CallId> {
+pub(crate) fn global_waiting(solution: &Solution, _problem: &Problem, _rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
     // Register each CallId with its aggregated waiting time.
     let mut aggregated_waiting: HashMap<CallId, Time> = HashMap::with_capacity(solution.len());
 
@@ -52,22 +53,21 @@ pub(crate) fn global_waiting(solution: &Solution, params: &RemovalParams) -> Vec
         .collect()
 }
 
-pub(crate) fn combined_cost(solution: &Solution, params: &RemovalParams) -> Vec<CallId> {
-    let mut thread_rng = rng();
+pub(crate) fn combined_cost(solution: &Solution, _problem: &Problem, rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
     let total_calls = solution.len() as f32;
-    
+
     let num_unassigned = max(min(
         (params.selection_ratio * 0.5 * (1.0 - params.assignment_bias) * total_calls) as usize,
         params.max_removals
     ), 1);
 
     // Get calls from unassigned/dummy
-    let mut unassigned_calls = random_unassigned(solution, num_unassigned);
-    
+    let mut unassigned_calls = random_unassigned(solution, rng, num_unassigned);
+
     if unassigned_calls.len() == params.max_removals {
         return unassigned_calls;
     }
-    
+
     let num_costly = max(
         (params.selection_ratio * 0.5 * params.assignment_bias * total_calls) as usize,
         params.min_removals
@@ -75,18 +75,368 @@ pub(crate) fn combined_cost(solution: &Solution, params: &RemovalParams) -> Vec<
 
     // Get most costly calls
     let mut costly_calls = global_cost(solution, num_costly);
-    
+
     let mut combined = Vec::with_capacity(unassigned_calls.len() + costly_calls.len());
     combined.append(&mut unassigned_calls);
     combined.append(&mut costly_calls);
-    
-    combined.shuffle(&mut thread_rng);
+
+    combined.shuffle(rng);
     
     let cut = max(min(params.max_removals, combined.len()), params.min_removals);
     
     combined.into_iter().take(cut).collect()
 }
 
+/// How strongly `cost_bias` sharpens the selection exponent in `worst_calls`:
+/// `p = 1 + cost_bias * WORST_CALLS_BIAS_SCALE`, so `cost_bias == 0.0` draws
+/// near-uniformly over the ranked calls while `cost_bias == 1.0` concentrates
+/// draws heavily on the costliest end of the list.
+const WORST_CALLS_BIAS_SCALE: f32 = 9.0;
+
+/// Ranks every assigned call by its marginal cost contribution (the same
+/// per-call total used by `global_cost`) and removes between `min_removals`
+/// and `max_removals` of them via a biased random draw: `idx = floor(y.powf(p)
+/// * L)` for `y` uniform in `[0, 1)`, so larger `p` (driven by `cost_bias`)
+/// concentrates removals on the costliest calls while staying stochastic.
+pub(crate) fn worst_calls(solution: &Solution, _problem: &Problem, rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
+    let mut contributions: Vec<(usize, i32)> = solution
+        .call_costs()
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _cost)| solution.call_assignments()[idx].is_some())
+        .map(|(idx, cost)| (idx, cost.total))
+        .collect();
+
+    if contributions.is_empty() {
+        return Vec::new();
+    }
+
+    contributions.sort_unstable_by_key(|&(_, total)| std::cmp::Reverse(total));
+
+    let total_calls = solution.len() as f32;
+    let target = min(
+        max(min((params.selection_ratio * total_calls) as usize, params.max_removals), params.min_removals),
+        contributions.len(),
+    );
+    let p = 1.0 + params.cost_bias * WORST_CALLS_BIAS_SCALE;
+    let l = contributions.len();
+
+    let mut picked: HashSet<usize> = HashSet::with_capacity(target);
+    let mut removals = Vec::with_capacity(target);
+
+    while removals.len() < target && picked.len() < l {
+        let y: f32 = rng.random_range(0.0..1.0);
+        let idx = ((y.powf(p) * l as f32) as usize).min(l - 1);
+
+        if picked.insert(idx) {
+            removals.push(CallId::try_from(contributions[idx].0 + 1).unwrap());
+        }
+    }
+
+    removals
+}
+
+/// Removes a cluster of *related* calls (Shaw removal) rather than
+/// independently-chosen ones: starts from a random assigned seed call, then
+/// repeatedly picks a reference call `r` from what's been removed so far,
+/// ranks every not-yet-removed call by `relatedness` to `r`, and removes the
+/// `p`-biased choice among them -- `idx = floor(y.powf(p) * len)` for `y`
+/// uniform in `[0, 1)`, so `params.shaw_determinism == 1.0` draws near-
+/// uniformly over the ranked calls while a larger value concentrates draws
+/// on the most related end. Removing a geographically/temporally clustered
+/// neighborhood together, instead of scattered individual calls, lets the
+/// reinsertion operators reshuffle the whole neighborhood at once.
+pub(crate) fn shaw_removal(solution: &Solution, problem: &Problem, rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
+    let assigned: Vec<CallId> = solution
+        .call_assignments()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, assignment)| assignment.map(|_| CallId::try_from(idx + 1).unwrap()))
+        .collect();
+
+    if assigned.is_empty() {
+        return Vec::new();
+    }
+
+    let total_calls = solution.len() as f32;
+    let target = min(
+        max(min((params.selection_ratio * total_calls) as usize, params.max_removals), params.min_removals),
+        assigned.len(),
+    );
+
+    let seed = assigned[rng.random_range(0..assigned.len())];
+
+    let mut removed = vec![seed];
+    let mut remaining: Vec<CallId> = assigned.into_iter().filter(|&c| c != seed).collect();
+
+    while removed.len() < target && !remaining.is_empty() {
+        let r = removed[rng.random_range(0..removed.len())];
+
+        remaining.sort_by(|&a, &b| {
+            relatedness(solution, problem, params, r, a)
+                .partial_cmp(&relatedness(solution, problem, params, r, b))
+                .unwrap()
+        });
+
+        let y: f32 = rng.random_range(0.0..1.0);
+        let idx = ((y.powf(params.shaw_determinism) * remaining.len() as f32) as usize).min(remaining.len() - 1);
+
+        removed.push(remaining.remove(idx));
+    }
+
+    removed
+}
+
+/// Shaw's relatedness measure between `a` and `b`: a weighted sum of
+/// normalized pickup-node distance, normalized delivery-node distance,
+/// normalized pickup time-window start difference, normalized cargo-size
+/// difference, and compatible-vehicle-set distance (all five via
+/// `Problem::relatedness_terms`, precomputed since they don't depend on the
+/// current solution), plus a same-vehicle indicator (`0.0` if `a` and `b`
+/// are currently assigned to the same vehicle, `1.0` otherwise) -- the one
+/// term that *does* depend on the current solution, so it's computed here
+/// rather than precomputed. Smaller is more related.
+fn relatedness(solution: &Solution, problem: &Problem, params: &RemovalParams, a: CallId, b: CallId) -> f32 {
+    let (pickup_distance, delivery_distance, time_window_diff, load_diff, vehicle_compatibility) =
+        problem.relatedness_terms(a, b);
+
+    let same_vehicle = solution.call_assignments()[a.index()].is_some()
+        && solution.call_assignments()[a.index()] == solution.call_assignments()[b.index()];
+    let vehicle_indicator = if same_vehicle { 0.0 } else { 1.0 };
+
+    params.shaw_pickup_weight * pickup_distance
+        + params.shaw_delivery_weight * delivery_distance
+        + params.shaw_time_weight * time_window_diff
+        + params.shaw_load_weight * load_diff
+        + params.shaw_compatibility_weight * vehicle_compatibility
+        + params.shaw_vehicle_weight * vehicle_indicator
+}
+
+/// Tears out a geographically connected blob of assigned calls via a flood
+/// fill, rather than the scattered picks `random_calls` makes: from a
+/// random seed call, BFS over the adjacency relation "within
+/// `params.cluster_adjacency_threshold` of each other in normalized
+/// pickup-node distance" (the same precomputed term `shaw_removal` weighs,
+/// via `Problem::calls_within`) -- push the seed, mark it visited,
+/// pop a call, enqueue its unvisited assigned neighbours closest-first,
+/// repeat -- growing the visited set until it reaches the target size
+/// `worst_calls` and `shaw_removal` derive from min/max/selection_ratio, or the flood runs
+/// out of reachable calls first. A compact spatial cluster like this lets
+/// the repair step meaningfully reorganize several routes at once instead
+/// of patching isolated gaps. `calls_within` is a binary search into
+/// `ProblemIndex::neighbor_order`'s precomputed, presorted distances, so
+/// each BFS pop no longer rescans and re-sorts every assigned call.
+pub(crate) fn cluster_removal(solution: &Solution, problem: &Problem, rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
+    let assigned: Vec<CallId> = solution
+        .call_assignments()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, assignment)| assignment.map(|_| CallId::try_from(idx + 1).unwrap()))
+        .collect();
+
+    if assigned.is_empty() {
+        return Vec::new();
+    }
+
+    let assigned_set: HashSet<CallId> = assigned.iter().copied().collect();
+
+    let total_calls = solution.len() as f32;
+    let target = min(
+        max(min((params.selection_ratio * total_calls) as usize, params.max_removals), params.min_removals),
+        assigned.len(),
+    );
+
+    let seed = assigned[rng.random_range(0..assigned.len())];
+
+    let mut visited: HashSet<CallId> = HashSet::with_capacity(target);
+    let mut order: Vec<CallId> = Vec::with_capacity(target);
+    let mut queue: VecDeque<CallId> = VecDeque::new();
+
+    visited.insert(seed);
+    order.push(seed);
+    queue.push_back(seed);
+
+    while order.len() < target {
+        let Some(current) = queue.pop_front() else {
+            break;
+        };
+
+        let neighbours = problem.calls_within(current, params.cluster_adjacency_threshold);
+
+        for &(neighbour, _) in neighbours {
+            if order.len() >= target {
+                break;
+            }
+
+            if assigned_set.contains(&neighbour) && visited.insert(neighbour) {
+                order.push(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    order
+}
+
+/// Ranks every assigned call by its current detour cost: the extra travel
+/// cost its route pays for routing through the call's pickup and delivery
+/// instead of straight from whatever precedes the pickup to whatever follows
+/// the delivery, plus its fixed port handling cost. This is the same
+/// before/after travel-cost arithmetic `solution::feasibility::insertion_delta_cost`
+/// uses to score a hypothetical insertion, applied in reverse to a call
+/// already in the route. Removes a `cost_bias`-weighted random draw among the
+/// costliest, the same biased-draw idiom `worst_calls` uses over realized
+/// `call_costs().total` contributions.
+pub(crate) fn detour_removal(solution: &Solution, problem: &Problem, rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
+    let mut contributions: Vec<(CallId, Cost)> = Vec::new();
+
+    for (vehicle_idx, route) in solution.routes().iter().enumerate() {
+        let vehicle = VehicleId::new((vehicle_idx + 1) as u8).unwrap();
+        let route_calls = route.route();
+
+        for (pos, &call) in route_calls.iter().enumerate() {
+            if call.is_delivery() {
+                continue;
+            }
+
+            let delivery_pos = match route_calls.iter().position(|&c| c == call.delivery()) {
+                Some(idx) => idx,
+                None => continue,
+            };
+
+            let p_node = if pos == 0 {
+                problem.get_vehicle(vehicle).home_node
+            } else {
+                let prev = route_calls[pos - 1];
+                if prev.is_pickup() {
+                    problem.origin_node(prev)
+                } else {
+                    problem.destination_node(prev)
+                }
+            };
+
+            let d_node = if delivery_pos + 1 < route_calls.len() {
+                let next = route_calls[delivery_pos + 1];
+                if next.is_pickup() {
+                    problem.origin_node(next)
+                } else {
+                    problem.destination_node(next)
+                }
+            } else {
+                problem.get_vehicle(vehicle).home_node
+            };
+
+            let pickup_node = problem.origin_node(call);
+            let delivery_node = problem.destination_node(call);
+
+            let through_cost = problem.get_travel_cost(vehicle, p_node, pickup_node)
+                + problem.get_travel_cost(vehicle, pickup_node, delivery_node)
+                + problem.get_travel_cost(vehicle, delivery_node, d_node);
+            let skip_cost = problem.get_travel_cost(vehicle, p_node, d_node);
+
+            let detour = (through_cost - skip_cost) + problem.port_cost_for_call(vehicle, call);
+            contributions.push((call, detour));
+        }
+    }
+
+    if contributions.is_empty() {
+        return Vec::new();
+    }
+
+    contributions.sort_unstable_by_key(|&(_, detour)| std::cmp::Reverse(detour));
+
+    let total_calls = solution.len() as f32;
+    let target = min(
+        max(min((params.selection_ratio * total_calls) as usize, params.max_removals), params.min_removals),
+        contributions.len(),
+    );
+    let p = 1.0 + params.cost_bias * WORST_CALLS_BIAS_SCALE;
+    let l = contributions.len();
+
+    let mut picked: HashSet<usize> = HashSet::with_capacity(target);
+    let mut removals = Vec::with_capacity(target);
+
+    while removals.len() < target && picked.len() < l {
+        let y: f32 = rng.random_range(0.0..1.0);
+        let idx = ((y.powf(p) * l as f32) as usize).min(l - 1);
+
+        if picked.insert(idx) {
+            removals.push(contributions[idx].0);
+        }
+    }
+
+    removals
+}
+
+/// Like `global_cost`, but instead of a strict top-`amount` prefix, draws
+/// the next worst call as `idx = floor(y.powf(worst_exp) * remaining.len())`
+/// for `y` uniform in `[0, 1)` -- the same biased-draw idiom `worst_calls`
+/// uses, here applied while removing from the ranked list so the same
+/// handful of costliest calls aren't torn out every single iteration -- and
+/// for each worst call drawn, also removes its `worst_skip` nearest
+/// still-assigned neighbours (by the precomputed normalized pickup-location
+/// distance in `Problem::relatedness_terms`). Tearing out a structurally
+/// costly call together with its spatial neighbourhood, rather than just
+/// the call itself, gives the repair step a whole region to rebuild instead
+/// of one awkward gap.
+pub(crate) fn worst_job_removal(solution: &Solution, problem: &Problem, rng: &mut dyn RngCore, params: &RemovalParams) -> Vec<CallId> {
+    let mut contributions: Vec<(usize, i32)> = solution
+        .call_costs()
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _cost)| solution.call_assignments()[idx].is_some())
+        .map(|(idx, cost)| (idx, cost.total))
+        .collect();
+
+    if contributions.is_empty() {
+        return Vec::new();
+    }
+
+    contributions.sort_unstable_by_key(|&(_, total)| std::cmp::Reverse(total));
+
+    let total_calls = solution.len() as f32;
+    let target = min(
+        max(min((params.selection_ratio * total_calls) as usize, params.max_removals), params.min_removals),
+        contributions.len(),
+    );
+
+    let mut remaining: Vec<CallId> = contributions.into_iter().map(|(idx, _)| CallId::try_from(idx + 1).unwrap()).collect();
+
+    let mut removed: HashSet<CallId> = HashSet::with_capacity(target);
+    let mut order: Vec<CallId> = Vec::with_capacity(target);
+
+    while order.len() < target && !remaining.is_empty() {
+        let y: f32 = rng.random_range(0.0..1.0);
+        let idx = ((y.powf(params.worst_exp) * remaining.len() as f32) as usize).min(remaining.len() - 1);
+        let worst = remaining.remove(idx);
+
+        if removed.insert(worst) {
+            order.push(worst);
+        }
+
+        let mut neighbours: Vec<(usize, CallId, f32)> = remaining
+            .iter()
+            .enumerate()
+            .map(|(i, &call)| (i, call, problem.relatedness_terms(worst, call).0))
+            .collect();
+        neighbours.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+
+        for (_, neighbour, _) in neighbours.into_iter().take(params.worst_skip) {
+            if order.len() >= target {
+                break;
+            }
+
+            if removed.insert(neighbour) {
+                order.push(neighbour);
+            }
+        }
+
+        remaining.retain(|&call| !removed.contains(&call));
+    }
+
+    order
+}
+
 pub(crate) fn global_cost(solution: &Solution, amount: usize) -> Vec<CallId> {
     let mut costs: Vec<(usize, _)> = solution
         .call_costs()
@@ -107,12 +457,11 @@ pub(crate) fn global_cost(solution: &Solution, amount: usize) -> Vec<CallId> {
         .collect()
 }
 
-pub(crate) fn broken_vehicle(solution: &Solution, _params: &RemovalParams) -> Vec<CallId> {
-    let mut thread_rng = rand::rng();
+pub(crate) fn broken_vehicle(solution: &Solution, _problem: &Problem, rng: &mut dyn RngCore, _params: &RemovalParams) -> Vec<CallId> {
     let vehicle_count = solution.routes().len();
 
     for _ in 0..vehicle_count {
-        let vehicle_index = thread_rng.random_range(1..=vehicle_count);
+        let vehicle_index = rng.random_range(1..=vehicle_count);
         let route = solution.route(vehicle_index.try_into().unwrap());
 
         if !route.is_empty() {
@@ -123,10 +472,9 @@ pub(crate) fn broken_vehicle(solution: &Solution, _params: &RemovalParams) -> Ve
     Vec::new()
 }
 
-pub(crate) fn random_calls(solution: &Solution, amount: usize) -> Vec<CallId> {
+pub(crate) fn random_calls(solution: &Solution, rng: &mut dyn RngCore, amount: usize) -> Vec<CallId> {
     let n = solution.call_assignments().len();
-    let mut thread_rng = rng();
-    sample(&mut thread_rng, n, amount)
+    sample(rng, n, amount)
         .iter()
         .map(|idx| {
             (idx + 1)
@@ -136,7 +484,7 @@ pub(crate) fn random_calls(solution: &Solution, amount: usize) -> Vec<CallId> {
         .collect::<Vec<CallId>>()
 }
 
-pub(crate) fn random_unassigned(solution: &Solution, amount: usize) -> Vec<CallId> {
+pub(crate) fn random_unassigned(solution: &Solution, rng: &mut dyn RngCore, amount: usize) -> Vec<CallId> {
     let unassigned_calls: Vec<usize> = solution
         .call_assignments()
         .iter()
@@ -150,13 +498,12 @@ pub(crate) fn random_unassigned(solution: &Solution, amount: usize) -> Vec<CallI
         })
         .collect();
 
-    let mut thread_rng = rng();
     let sample_size = amount.min(unassigned_calls.len());
-    let sampled_indices = sample(&mut thread_rng, unassigned_calls.len(), sample_size);
+    let sampled_indices = sample(rng, unassigned_calls.len(), sample_size);
 
     sampled_indices
         .iter()
-        .map(|idx| 
+        .map(|idx|
             (unassigned_calls[idx] + 1)
                 .try_into()
                 .expect("Out of range value generated for CallId")
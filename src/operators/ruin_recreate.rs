@@ -0,0 +1,269 @@
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::problem::Problem;
+use crate::solution::Solution;
+use crate::types::{CallId, Cost, VehicleId};
+
+/// Parameters for a single ruin-and-recreate application.
+#[derive(Clone, Copy)]
+pub struct RuinRecreateParams {
+    /// Number of calls to ruin (remove) before recreating.
+    pub q: usize,
+    /// Number of vehicles considered when computing regret during recreate.
+    pub k: usize,
+    /// Weight of travel distance in the Shaw relatedness measure.
+    pub distance_weight: f32,
+    /// Weight of the pickup time-window start difference in the Shaw
+    /// relatedness measure.
+    pub time_window_weight: f32,
+    /// Weight of cargo-size similarity in the Shaw relatedness measure.
+    pub cargo_weight: f32,
+}
+
+impl Default for RuinRecreateParams {
+    fn default() -> Self {
+        RuinRecreateParams {
+            q: 5,
+            k: 3,
+            distance_weight: 9.0,
+            time_window_weight: 3.0,
+            cargo_weight: 2.0,
+        }
+    }
+}
+
+/// Ruins `q` random assigned calls from `solution`, returning the removed calls.
+pub(crate) fn random_ruin(solution: &mut Solution, q: usize) -> Vec<CallId> {
+    let assigned: Vec<CallId> = solution
+        .call_assignments()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, assignment)| {
+            assignment.map(|_| CallId::new_pickup((idx + 1) as i16).unwrap())
+        })
+        .collect();
+
+    let amount = q.min(assigned.len());
+    let mut thread_rng = rand::rng();
+    let picked: Vec<CallId> = sample(&mut thread_rng, assigned.len(), amount)
+        .iter()
+        .map(|idx| assigned[idx])
+        .collect();
+
+    for &call in &picked {
+        let _ = solution.remove_call(call);
+    }
+
+    picked
+}
+
+/// Shaw-style related removal: picks a random seed call, then removes the
+/// `q - 1` still-assigned calls most related to that seed, where
+/// relatedness is a weighted sum of travel distance, pickup time-window start
+/// difference, and cargo-size difference (smaller is more related; how much
+/// the windows overlap is not measured).
+pub(crate) fn shaw_ruin(solution: &mut Solution, problem: &Problem, params: &RuinRecreateParams) -> Vec<CallId> {
+    let mut assigned: Vec<CallId> = solution
+        .call_assignments()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, assignment)| {
+            assignment.map(|_| CallId::new_pickup((idx + 1) as i16).unwrap())
+        })
+        .collect();
+
+    if assigned.is_empty() {
+        return Vec::new();
+    }
+
+    let mut thread_rng = rand::rng();
+    let seed_idx = thread_rng.random_range(0..assigned.len());
+    let seed = assigned.swap_remove(seed_idx);
+
+    let mut removed = vec![seed];
+    let amount = params.q.min(removed.len() + assigned.len());
+
+    while removed.len() < amount && !assigned.is_empty() {
+        let reference = seed;
+
+        assigned.sort_by(|&a, &b| {
+            relatedness(problem, reference, a, params)
+                .partial_cmp(&relatedness(problem, reference, b, params))
+                .unwrap()
+        });
+
+        removed.push(assigned.remove(0));
+    }
+
+    for &call in &removed {
+        let _ = solution.remove_call(call);
+    }
+
+    removed
+}
+
+/// Relatedness of `b` to `a`: a weighted sum of pickup-travel distance,
+/// pickup time-window start difference, and cargo-size difference. Lower
+/// means more related.
+fn relatedness(problem: &Problem, a: CallId, b: CallId, params: &RuinRecreateParams) -> f32 {
+    let vehicles = problem.get_compatible_vehicles(a.pickup());
+    let vehicle = vehicles.first().copied().or_else(|| {
+        problem.get_compatible_vehicles(b.pickup()).first().copied()
+    });
+
+    let distance = match vehicle {
+        Some(v) => problem.get_travel_time(v, problem.origin_node(a), problem.origin_node(b)) as f32,
+        None => 0.0,
+    };
+
+    let a_window = problem.pickup_time_window(a);
+    let b_window = problem.pickup_time_window(b);
+    let tw_gap = (*a_window.start() as f32 - *b_window.start() as f32).abs();
+
+    let cargo_gap = (problem.cargo_size(a) as f32 - problem.cargo_size(b) as f32).abs();
+
+    params.distance_weight * distance
+        + params.time_window_weight * tw_gap
+        + params.cargo_weight * cargo_gap
+}
+
+/// Reinserts every currently-unassigned call using regret-k insertion: for each
+/// unassigned call, compute the cheapest feasible insertion cost per compatible
+/// vehicle, rank the call by the regret of delaying it (the sum of the gaps
+/// between the best vehicle and the next `k - 1` vehicles), and insert the call
+/// with the largest regret at its cheapest feasible position. Calls with no
+/// feasible insertion are left unassigned (paying `not_transport_cost`).
+pub(crate) fn regret_k_recreate(solution: &mut Solution, problem: &Problem, k: usize) -> (usize, usize) {
+    let mut evaluated = 0;
+    let mut infeasible = 0;
+
+    loop {
+        let unassigned: Vec<CallId> = solution
+            .call_assignments()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, assignment)| {
+                if assignment.is_none() {
+                    CallId::new_pickup((idx + 1) as i16)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if unassigned.is_empty() {
+            break;
+        }
+
+        let mut best_call: Option<(CallId, VehicleId, usize, usize, Cost, f32)> = None;
+
+        for &call in &unassigned {
+            let mut per_vehicle_best: Vec<(Cost, VehicleId, usize, usize)> = Vec::new();
+
+            for &vehicle in problem.get_compatible_vehicles(call.pickup()) {
+                let (_, capacity_result) = solution.find_spare_capacity_in_vehicle(problem, call, vehicle);
+                if capacity_result.is_none() {
+                    continue;
+                }
+                let capacity_result = capacity_result.clone();
+
+                let candidates: Vec<(usize, usize)> = solution
+                    .get_feasible_insertions(problem, call, vehicle, &capacity_result)
+                    .collect();
+
+                let mut cheapest: Option<(Cost, usize, usize)> = None;
+                for (pickup_idx, delivery_idx) in candidates {
+                    evaluated += 1;
+
+                    if solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx).is_err() {
+                        continue;
+                    }
+
+                    if solution.feasible(problem).is_err() {
+                        let _ = solution.remove_call(call);
+                        infeasible += 1;
+                        continue;
+                    }
+
+                    let cost = solution.cost(problem);
+                    let _ = solution.remove_call(call);
+
+                    if cheapest.map_or(true, |(c, _, _)| cost < c) {
+                        cheapest = Some((cost, pickup_idx, delivery_idx));
+                    }
+                }
+
+                if let Some((cost, pickup_idx, delivery_idx)) = cheapest {
+                    per_vehicle_best.push((cost, vehicle, pickup_idx, delivery_idx));
+                }
+            }
+
+            if per_vehicle_best.is_empty() {
+                continue;
+            }
+
+            per_vehicle_best.sort_by_key(|&(cost, _, _, _)| cost);
+
+            let (best_cost, best_vehicle, best_pickup, best_delivery) = per_vehicle_best[0];
+
+            // Vehicles beyond those with a feasible insertion contribute the
+            // largest possible regret so hard-to-place calls are prioritized.
+            let regret: f32 = (1..k)
+                .map(|i| {
+                    per_vehicle_best
+                        .get(i)
+                        .map(|&(cost, _, _, _)| (cost - best_cost) as f32)
+                        .unwrap_or(f32::MAX / (k as f32))
+                })
+                .sum();
+
+            if best_call.as_ref().map_or(true, |&(_, _, _, _, _, r)| regret > r) {
+                best_call = Some((call, best_vehicle, best_pickup, best_delivery, best_cost, regret));
+            }
+        }
+
+        match best_call {
+            Some((call, vehicle, pickup_idx, delivery_idx, _, _)) => {
+                let _ = solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx);
+            }
+            None => {
+                // No unassigned call has a feasible insertion anywhere; stop and
+                // leave the remainder in the dummy pool.
+                infeasible += unassigned.len();
+                break;
+            }
+        }
+    }
+
+    (evaluated, infeasible)
+}
+
+/// Ruin-and-recreate "operator": removes `params.q` calls (random or Shaw-related,
+/// chosen with equal probability) and reinserts all unassigned calls with
+/// regret-`params.k` insertion. Usable as a single operator inside the SA/ALNS loop.
+pub fn ruin_and_recreate(solution: &mut Solution, problem: &Problem, params: &RuinRecreateParams) -> (usize, usize) {
+    let mut thread_rng = rand::rng();
+
+    if thread_rng.random_bool(0.5) {
+        random_ruin(solution, params.q);
+    } else {
+        shaw_ruin(solution, problem, params);
+    }
+
+    regret_k_recreate(solution, problem, params.k)
+}
+
+pub const PARAMS: RuinRecreateParams = RuinRecreateParams {
+    q: 5,
+    k: 3,
+    distance_weight: 9.0,
+    time_window_weight: 3.0,
+    cargo_weight: 2.0,
+};
+
+/// `ruin_and_recreate` with the tuned default parameters, so it can be dropped
+/// into the SA loop the same way as `operators::mutate::roulette_wheel_tuned`.
+pub fn ruin_and_recreate_tuned(solution: &mut Solution, problem: &Problem) -> (usize, usize) {
+    ruin_and_recreate(solution, problem, &PARAMS)
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::problem::index::ProblemIndex;
+use crate::problem::Problem;
+use crate::types::{CallId, VehicleId};
+use crate::utils::IntervalTree;
+
+/// Default directory `Problem::load` writes precomputed `ProblemIndex` files
+/// to when the caller doesn't override it via `Problem::load_with_cache`.
+pub const DEFAULT_CACHE_DIR: &str = "cache/problem_index";
+
+/// Builds `problem`'s `ProblemIndex`, transparently caching the result on
+/// disk under `cache_dir` keyed by a content hash of `raw_bytes` (the
+/// instance file `Problem::load` just parsed). `force_recompute` skips
+/// reading back an existing cache file, but a freshly rebuilt index is
+/// still written there. Falls back to a plain `ProblemIndex::new` rebuild
+/// whenever the cache file is missing, stale, or fails to parse -- the
+/// cache is purely a speed-up, never a correctness requirement.
+pub(super) fn load_or_build_index(
+    problem: &Problem,
+    raw_bytes: &[u8],
+    cache_dir: &Path,
+    force_recompute: bool,
+) -> ProblemIndex {
+    let path = cache_file_path(cache_dir, content_hash(raw_bytes));
+
+    if !force_recompute {
+        if let Some(index) = fs::read(&path).ok().and_then(|bytes| deserialize_index(&bytes)) {
+            return index;
+        }
+    }
+
+    let index = ProblemIndex::new(problem);
+
+    if fs::create_dir_all(cache_dir).is_ok() {
+        let _ = fs::write(&path, serialize_index(&index));
+    }
+
+    index
+}
+
+fn cache_file_path(cache_dir: &Path, hash: u64) -> PathBuf {
+    cache_dir.join(format!("{:016x}.idx", hash))
+}
+
+/// Lightweight, non-cryptographic content hash (FNV-1a, 64-bit) of the raw
+/// instance bytes, used only to name cache files. A collision would just
+/// serve one instance's cached index to another, which `Solution::feasible`
+/// checks elsewhere would expose immediately, so a cryptographic hash isn't
+/// warranted for a build-speed cache key.
+fn content_hash(bytes: &[u8]) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(FNV_OFFSET, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+fn serialize_index(index: &ProblemIndex) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_vehicle_groups(&mut buf, &index.cargo_vessel);
+    write_interval_tree(&mut buf, &index.pickup_tree);
+    write_interval_tree(&mut buf, &index.delivery_tree);
+    write_call_groups(&mut buf, &index.call_clusters);
+    buf
+}
+
+fn deserialize_index(bytes: &[u8]) -> Option<ProblemIndex> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+
+    let cargo_vessel = read_vehicle_groups(&mut cursor)?;
+    let pickup_tree = read_interval_tree(&mut cursor)?;
+    let delivery_tree = read_interval_tree(&mut cursor)?;
+    let call_clusters = read_call_groups(&mut cursor)?;
+
+    Some(ProblemIndex { cargo_vessel, pickup_tree, delivery_tree, call_clusters })
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes: [u8; 4] = self.bytes.get(self.pos..self.pos + 4)?.try_into().ok()?;
+        self.pos += 4;
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    fn read_i16(&mut self) -> Option<i16> {
+        let bytes: [u8; 2] = self.bytes.get(self.pos..self.pos + 2)?.try_into().ok()?;
+        self.pos += 2;
+        Some(i16::from_le_bytes(bytes))
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+}
+
+fn write_vehicle_groups(buf: &mut Vec<u8>, groups: &[Vec<VehicleId>]) {
+    buf.extend_from_slice(&(groups.len() as u32).to_le_bytes());
+    for group in groups {
+        buf.extend_from_slice(&(group.len() as u32).to_le_bytes());
+        for &vehicle in group {
+            buf.push(vehicle.get());
+        }
+    }
+}
+
+fn read_vehicle_groups(cursor: &mut Cursor) -> Option<Vec<Vec<VehicleId>>> {
+    let count = cursor.read_u32()?;
+    (0..count)
+        .map(|_| {
+            let len = cursor.read_u32()?;
+            (0..len).map(|_| VehicleId::new(cursor.read_u8()?)).collect()
+        })
+        .collect()
+}
+
+fn write_call_groups(buf: &mut Vec<u8>, groups: &[Vec<CallId>]) {
+    buf.extend_from_slice(&(groups.len() as u32).to_le_bytes());
+    for group in groups {
+        buf.extend_from_slice(&(group.len() as u32).to_le_bytes());
+        for &call in group {
+            buf.extend_from_slice(&call.raw().to_le_bytes());
+        }
+    }
+}
+
+fn read_call_groups(cursor: &mut Cursor) -> Option<Vec<Vec<CallId>>> {
+    let count = cursor.read_u32()?;
+    (0..count)
+        .map(|_| {
+            let len = cursor.read_u32()?;
+            (0..len).map(|_| CallId::from_raw(cursor.read_i16()?)).collect()
+        })
+        .collect()
+}
+
+fn write_interval_tree(buf: &mut Vec<u8>, tree: &IntervalTree) {
+    let entries: Vec<_> = tree.entries().collect();
+    buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (call, window) in entries {
+        buf.extend_from_slice(&call.raw().to_le_bytes());
+        buf.extend_from_slice(&window.start().to_le_bytes());
+        buf.extend_from_slice(&window.end().to_le_bytes());
+    }
+}
+
+fn read_interval_tree(cursor: &mut Cursor) -> Option<IntervalTree> {
+    let count = cursor.read_u32()?;
+    let entries: Option<Vec<_>> = (0..count)
+        .map(|_| {
+            let call = CallId::from_raw(cursor.read_i16()?)?;
+            let start = cursor.read_i16()?;
+            let end = cursor.read_i16()?;
+            Some((call, start..=end))
+        })
+        .collect();
+    Some(IntervalTree::new(entries?))
+}
@@ -0,0 +1,394 @@
+use crate::problem::dimension::Dimension;
+use crate::problem::index::ProblemIndex;
+use crate::problem::locks::LockSet;
+use crate::problem::{CallParameters, Problem, Vehicle};
+use crate::types::*;
+use crate::utils::Matrix2;
+
+/// Parameters governing the vicinity-clustering preprocessing pass, modeled
+/// on vrp-pragmatic's clustering: calls are only merged into a composite
+/// "cluster call" when their pickup (and delivery) locations are mutually
+/// close in travel time/cost *and* their time windows overlap enough to
+/// plausibly be served back-to-back.
+#[derive(Clone, Copy, Debug)]
+pub struct ClusteringPolicy {
+    /// Maximum travel time (for some vehicle common to both calls) between
+    /// the calls' pickup nodes, and separately between their delivery nodes,
+    /// for them to be considered "close".
+    pub duration_threshold: Time,
+    /// Maximum travel cost between the calls' pickup nodes, and separately
+    /// between their delivery nodes, for them to be considered "close".
+    pub distance_threshold: Cost,
+    /// Minimum overlap required between the calls' pickup windows, and
+    /// separately between their delivery windows.
+    pub min_window_overlap: Time,
+    /// Largest number of calls a single cluster may absorb.
+    pub max_cluster_size: usize,
+}
+
+/// Maps each call id in a clustered `Problem` back to the original calls it
+/// stands in for. Singleton (unclustered) calls map to themselves; composite
+/// cluster calls map to their members in visiting order, as chained by a
+/// nearest-neighbor walk over their pickup nodes at cluster time.
+#[derive(Clone, Debug, Default)]
+pub struct ClusterMap {
+    /// Indexed by the clustered call's `index()`; member ids are in pickup form.
+    members: Vec<Vec<CallId>>,
+}
+
+impl ClusterMap {
+    /// The original calls (pickup form) a clustered call stands in for, in
+    /// visiting order. A single-element slice means `call` wasn't merged.
+    pub fn members(&self, call: CallId) -> &[CallId] {
+        &self.members[call.pickup().index()]
+    }
+
+    /// Whether `call` is a composite cluster rather than a passed-through call.
+    pub fn is_cluster(&self, call: CallId) -> bool {
+        self.members(call).len() > 1
+    }
+
+    /// Expands a route's call sequence (as returned by `Solution::route` on
+    /// the clustered problem) into the corresponding original call ids, so
+    /// `Solution::to_pylist` can report the original instance's call ids. A
+    /// cluster's pickup expands to its members' pickups in visiting order;
+    /// its delivery expands to their deliveries in the reverse order, since a
+    /// cluster is picked up and dropped off as a LIFO stack of its members.
+    pub fn expand(&self, calls: &[CallId]) -> Vec<CallId> {
+        calls
+            .iter()
+            .flat_map(|&call| {
+                let members = self.members(call);
+                if call.is_pickup() {
+                    members.iter().map(|m| m.pickup()).collect::<Vec<_>>()
+                } else {
+                    members.iter().rev().map(|m| m.delivery()).collect::<Vec<_>>()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Builds the reduced, clustered `Problem` and its `ClusterMap` for `problem`
+/// under `policy`. See `Problem::cluster`.
+pub(super) fn build(problem: &Problem, policy: &ClusteringPolicy) -> (Problem, ClusterMap) {
+    let n = problem.n_calls.index() + 1;
+
+    // Greedily agglomerate calls: collect every pair that's mutually close
+    // enough, sort the closest first, and merge their groups unless that
+    // would exceed `max_cluster_size` or leave the group with no vehicle
+    // able to serve every member.
+    let mut candidates = Vec::new();
+    for i in 0..n {
+        let call_i = CallId::new_pickup((i + 1) as i16).unwrap();
+        for j in (i + 1)..n {
+            let call_j = CallId::new_pickup((j + 1) as i16).unwrap();
+            if let Some(closeness) = pair_closeness(problem, policy, call_i, call_j) {
+                candidates.push((closeness, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut groups: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut group_of: Vec<usize> = (0..n).collect();
+
+    for (_, i, j) in candidates {
+        let (gi, gj) = (group_of[i], group_of[j]);
+        if gi == gj {
+            continue;
+        }
+
+        let merged_size = groups[gi].len() + groups[gj].len();
+        if merged_size > policy.max_cluster_size {
+            continue;
+        }
+        if common_vehicles(problem, &groups[gi], &groups[gj]).is_none() {
+            continue;
+        }
+
+        let moved = std::mem::take(&mut groups[gj]);
+        for &idx in &moved {
+            group_of[idx] = gi;
+        }
+        groups[gi].extend(moved);
+    }
+
+    let mut clusters: Vec<Vec<usize>> = groups.into_iter().filter(|g| !g.is_empty()).collect();
+    clusters.sort_by_key(|g| g[0]);
+    for cluster in &mut clusters {
+        order_by_nearest_neighbor(problem, cluster);
+    }
+
+    assemble(problem, &clusters)
+}
+
+/// `None` if the two calls can't share a cluster (no common vehicle, a
+/// pickup or delivery leg over `policy`'s duration or distance threshold,
+/// or too little time-window overlap); otherwise the combined
+/// pickup+delivery travel time plus travel cost, used to merge the closest
+/// pairs first.
+fn pair_closeness(problem: &Problem, policy: &ClusteringPolicy, a: CallId, b: CallId) -> Option<f64> {
+    let vehicles = common_vehicles(problem, &[a.index()], &[b.index()])?;
+
+    let pickup_time = vehicles
+        .iter()
+        .map(|&v| problem.get_travel_time(v, problem.origin_node(a), problem.origin_node(b)))
+        .min()?;
+    let delivery_time = vehicles
+        .iter()
+        .map(|&v| problem.get_travel_time(v, problem.destination_node(a), problem.destination_node(b)))
+        .min()?;
+    if pickup_time > policy.duration_threshold || delivery_time > policy.duration_threshold {
+        return None;
+    }
+
+    let pickup_cost = vehicles
+        .iter()
+        .map(|&v| problem.get_travel_cost(v, problem.origin_node(a), problem.origin_node(b)))
+        .min()?;
+    let delivery_cost = vehicles
+        .iter()
+        .map(|&v| problem.get_travel_cost(v, problem.destination_node(a), problem.destination_node(b)))
+        .min()?;
+    if pickup_cost > policy.distance_threshold || delivery_cost > policy.distance_threshold {
+        return None;
+    }
+
+    if window_overlap(&problem.pickup_time_window(a), &problem.pickup_time_window(b)) < policy.min_window_overlap {
+        return None;
+    }
+    if window_overlap(&problem.delivery_time_window(a), &problem.delivery_time_window(b)) < policy.min_window_overlap {
+        return None;
+    }
+
+    Some((pickup_time + delivery_time) as f64 + (pickup_cost + delivery_cost) as f64)
+}
+
+fn window_overlap(a: &std::ops::RangeInclusive<Time>, b: &std::ops::RangeInclusive<Time>) -> Time {
+    let start = *a.start().max(b.start());
+    let end = *a.end().min(b.end());
+    end - start
+}
+
+/// Vehicles compatible with every call (by 0-indexed call index) in both
+/// `left` and `right`, or `None` if no vehicle serves them all.
+fn common_vehicles(problem: &Problem, left: &[usize], right: &[usize]) -> Option<Vec<VehicleId>> {
+    let call_at = |idx: usize| CallId::new_pickup((idx as i16) + 1).unwrap();
+
+    let mut indices = left.iter().chain(right.iter());
+    let mut common: Vec<VehicleId> = problem.get_compatible_vehicles(call_at(*indices.next()?)).to_vec();
+
+    for &idx in indices {
+        let allowed = problem.get_compatible_vehicles(call_at(idx));
+        common.retain(|v| allowed.contains(v));
+        if common.is_empty() {
+            return None;
+        }
+    }
+
+    if common.is_empty() {
+        None
+    } else {
+        Some(common)
+    }
+}
+
+/// Orders `cluster` (0-indexed call indices) into a visiting sequence by
+/// repeatedly walking to the nearest remaining call's origin node, using the
+/// first vehicle compatible with the whole cluster as the reference for
+/// travel time.
+fn order_by_nearest_neighbor(problem: &Problem, cluster: &mut Vec<usize>) {
+    if cluster.len() <= 1 {
+        return;
+    }
+
+    let reference = common_vehicles(problem, &cluster[..1], &cluster[1..])
+        .and_then(|v| v.first().copied())
+        .unwrap_or_else(|| VehicleId::from_index(0).unwrap());
+
+    let mut ordered = vec![cluster[0]];
+    let mut remaining: Vec<usize> = cluster[1..].to_vec();
+
+    while !remaining.is_empty() {
+        let current = CallId::new_pickup((*ordered.last().unwrap() as i16) + 1).unwrap();
+        let (pos, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(pos, &idx)| {
+                let candidate = CallId::new_pickup((idx as i16) + 1).unwrap();
+                let dist = problem.get_travel_time(
+                    reference,
+                    problem.origin_node(current),
+                    problem.origin_node(candidate),
+                );
+                (pos, dist)
+            })
+            .min_by_key(|&(_, dist)| dist)
+            .unwrap();
+        ordered.push(remaining.remove(pos));
+    }
+
+    *cluster = ordered;
+}
+
+/// Builds the reduced `Problem` and `ClusterMap` from the final grouping.
+/// Singleton groups pass through as their original call; groups with more
+/// than one member become a single composite call whose demand, penalty,
+/// and time windows summarize its members, and whose service time includes
+/// the intra-cluster travel needed to visit every member.
+fn assemble(problem: &Problem, clusters: &[Vec<usize>]) -> (Problem, ClusterMap) {
+    let n_vehicles = problem.n_vehicles.get() as usize;
+    let n_clusters = clusters.len();
+
+    let mut calls = Vec::with_capacity(n_clusters);
+    let mut members = Vec::with_capacity(n_clusters);
+    let mut vessel_cargo = Matrix2::new(n_vehicles, n_clusters, false);
+    let mut loading_time = Matrix2::new(n_vehicles, n_clusters, 0 as Time);
+    let mut unloading_time = Matrix2::new(n_vehicles, n_clusters, 0 as Time);
+    let mut port_cost = Matrix2::new(n_vehicles, n_clusters, 0 as Cost);
+
+    for (new_idx, cluster) in clusters.iter().enumerate() {
+        let member_calls: Vec<CallId> = cluster
+            .iter()
+            .map(|&idx| CallId::new_pickup((idx as i16) + 1).unwrap())
+            .collect();
+
+        let entry = member_calls[0];
+        let exit = *member_calls.last().unwrap();
+
+        let pickup_start = member_calls
+            .iter()
+            .map(|&c| *problem.pickup_time_window(c).start())
+            .max()
+            .unwrap();
+        let pickup_end = member_calls
+            .iter()
+            .map(|&c| *problem.pickup_time_window(c).end())
+            .min()
+            .unwrap();
+        let delivery_start = member_calls
+            .iter()
+            .map(|&c| *problem.delivery_time_window(c).start())
+            .max()
+            .unwrap();
+        let delivery_end = member_calls
+            .iter()
+            .map(|&c| *problem.delivery_time_window(c).end())
+            .min()
+            .unwrap();
+
+        calls.push(CallParameters {
+            origin: problem.origin_node(entry),
+            destination: problem.destination_node(exit),
+            size: member_calls.iter().map(|&c| problem.cargo_size(c)).sum(),
+            not_transport_cost: member_calls.iter().map(|&c| problem.not_transport_cost(c)).sum(),
+            pickup_window: pickup_start..=pickup_end,
+            delivery_window: delivery_start..=delivery_end,
+        });
+
+        for v in 0..n_vehicles {
+            let vehicle = VehicleId::from_index(v).unwrap();
+            let all_compatible = member_calls.iter().all(|&c| problem.is_call_allowed(vehicle, c));
+            *vessel_cargo.get_mut(v, new_idx) = all_compatible;
+
+            let mut load = member_calls.iter().map(|&c| problem.service_time(vehicle, c.pickup())).sum::<Time>();
+            let mut unload = member_calls.iter().map(|&c| problem.service_time(vehicle, c.delivery())).sum::<Time>();
+            for pair in member_calls.windows(2) {
+                load += problem.get_travel_time(vehicle, problem.origin_node(pair[0]), problem.origin_node(pair[1]));
+                unload += problem.get_travel_time(vehicle, problem.destination_node(pair[1]), problem.destination_node(pair[0]));
+            }
+            *loading_time.get_mut(v, new_idx) = load;
+            *unloading_time.get_mut(v, new_idx) = unload;
+            *port_cost.get_mut(v, new_idx) = member_calls.iter().map(|&c| problem.port_cost_for_call(vehicle, c)).sum();
+        }
+
+        members.push(member_calls);
+    }
+
+    // Each extra dimension's magnitude sums across a cluster's members, the
+    // same way the legacy weight dimension (`CallParameters::size`) does
+    // above; the per-vehicle bound is untouched by clustering.
+    let dimensions = problem
+        .dimensions
+        .iter()
+        .map(|dim| {
+            let magnitude = members
+                .iter()
+                .map(|member_calls| member_calls.iter().map(|&c| dim.magnitude(c)).sum())
+                .collect();
+            let bound = (0..n_vehicles)
+                .map(|v| dim.bound(VehicleId::from_index(v).unwrap()))
+                .collect();
+            Dimension::new(dim.name.clone(), magnitude, bound)
+        })
+        .collect();
+
+    let mut reduced = Problem {
+        n_nodes: problem.n_nodes,
+        n_vehicles: problem.n_vehicles,
+        n_calls: CallId::new_pickup(n_clusters as i16).unwrap(),
+        vehicles: problem.vehicles.iter().map(|v| Vehicle {
+            home_node: v.home_node,
+            starting_time: v.starting_time,
+            capacity: v.capacity,
+        }).collect(),
+        calls,
+        travel_time: problem.travel_time.clone(),
+        travel_cost: problem.travel_cost.clone(),
+        vessel_cargo,
+        loading_time,
+        unloading_time,
+        port_cost,
+        index: ProblemIndex::default(),
+        locks: LockSet::default(),
+        return_to_depot: problem.return_to_depot,
+        dimensions,
+        time_window_policy: problem.time_window_policy,
+    };
+    reduced.index = ProblemIndex::new(&reduced);
+
+    (reduced, ClusterMap { members })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(members: Vec<Vec<CallId>>) -> ClusterMap {
+        ClusterMap { members }
+    }
+
+    fn call(idx: i16) -> CallId {
+        CallId::new_pickup(idx).unwrap()
+    }
+
+    #[test]
+    fn expand_passes_singleton_calls_through_unchanged() {
+        let cluster_map = map(vec![vec![call(1)], vec![call(2)]]);
+
+        assert_eq!(cluster_map.expand(&[call(1).pickup(), call(2).delivery()]), vec![call(1).pickup(), call(2).delivery()]);
+        assert!(!cluster_map.is_cluster(call(1)));
+    }
+
+    #[test]
+    fn expand_pickup_uses_visiting_order_delivery_uses_reverse() {
+        // Call 1 is a composite cluster standing in for calls 2, 3, 4 (in
+        // visiting order, as `order_by_nearest_neighbor` would have chained
+        // them); its pickup expands forward, its delivery expands as a
+        // LIFO stack -- last picked up, first dropped off.
+        let cluster_map = map(vec![vec![call(2), call(3), call(4)]]);
+
+        assert!(cluster_map.is_cluster(call(1)));
+        assert_eq!(cluster_map.members(call(1)), &[call(2), call(3), call(4)]);
+
+        assert_eq!(
+            cluster_map.expand(&[call(1).pickup()]),
+            vec![call(2).pickup(), call(3).pickup(), call(4).pickup()]
+        );
+        assert_eq!(
+            cluster_map.expand(&[call(1).delivery()]),
+            vec![call(4).delivery(), call(3).delivery(), call(2).delivery()]
+        );
+    }
+}
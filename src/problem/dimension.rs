@@ -0,0 +1,41 @@
+use crate::types::{CallId, Capacity, VehicleId};
+
+/// A capacity dimension: a named accumulator with a per-call magnitude
+/// (applied as `+magnitude` at pickup and `-magnitude` at its paired
+/// delivery, the same signed-transit convention `Route::simulate` already
+/// uses for cargo weight) and a per-vehicle upper bound. Mirrors how
+/// capacitated routing engines register one unary transit callback per
+/// commodity, so a call can consume weight, volume, or pallet count
+/// simultaneously instead of each becoming its own bespoke scalar field.
+///
+/// The legacy weight dimension (`CallParameters::size` / `Vehicle::capacity`)
+/// is kept as-is and always checked; `Problem::dimensions` holds any
+/// *additional* dimensions layered on top of it.
+#[derive(Debug, Clone)]
+pub struct Dimension {
+    pub name: String,
+    /// Per-call magnitude, indexed by `call.index()`.
+    magnitude: Vec<Capacity>,
+    /// Per-vehicle upper bound, indexed by `vehicle.index()`.
+    bound: Vec<Capacity>,
+}
+
+impl Dimension {
+    pub fn new(name: impl Into<String>, magnitude: Vec<Capacity>, bound: Vec<Capacity>) -> Self {
+        Dimension {
+            name: name.into(),
+            magnitude,
+            bound,
+        }
+    }
+
+    /// The amount of this dimension `call` consumes (or frees, at delivery).
+    pub fn magnitude(&self, call: CallId) -> Capacity {
+        self.magnitude[call.index()]
+    }
+
+    /// The upper bound `vehicle` must never exceed in this dimension.
+    pub fn bound(&self, vehicle: VehicleId) -> Capacity {
+        self.bound[vehicle.index()]
+    }
+}
@@ -2,6 +2,33 @@ use crate::problem::Problem;
 use crate::types::*;
 use crate::utils::{Matrix2, IntervalTree};
 
+/// Percentile (in `[0, 1]`) of the finite pairwise call-distance
+/// distribution used as the single-linkage merge threshold in
+/// `ProblemIndex::cluster_calls`, so the threshold adapts to each instance's
+/// own travel-time scale instead of being a fixed constant.
+const CLUSTER_DISTANCE_PERCENTILE: f64 = 0.1;
+
+/// Static (solution-independent) components of Shaw's relatedness measure
+/// between two distinct calls: travel time between their pickup nodes,
+/// between their delivery nodes, the difference between their pickup
+/// time-window starts, and the difference between their cargo sizes, each
+/// normalized to `[0, 1]` by the largest such value seen across the
+/// instance; plus `vehicle_compatibility`, the fraction of each call's
+/// compatible vehicles the other *doesn't* share (already naturally in
+/// `[0, 1]`, so `0.0` means identical compatible-vehicle sets), all
+/// precomputed in `ProblemIndex::compute_relatedness`. The same-vehicle term
+/// of the full measure depends on the *current* solution's assignments, so
+/// `operators::removal::shaw_removal` weighs these precomputed terms
+/// together with that indicator at removal time.
+#[derive(Clone, Copy, Default)]
+pub(super) struct RelatednessTerms {
+    pub(super) pickup_distance: f32,
+    pub(super) delivery_distance: f32,
+    pub(super) time_window_diff: f32,
+    pub(super) load_diff: f32,
+    pub(super) vehicle_compatibility: f32,
+}
+
 /// Precomputed data structures
 #[derive(Default)]
 pub(super) struct ProblemIndex {
@@ -11,6 +38,24 @@ pub(super) struct ProblemIndex {
     pub(super) pickup_tree: IntervalTree,
     /// Interval tree for delivery windows
     pub(super) delivery_tree: IntervalTree,
+    /// Vicinity clusters of mutually close calls (see `cluster_calls`), for
+    /// insertion heuristics that want to place a whole neighborhood of calls
+    /// cheaply rather than one call at a time.
+    pub(super) call_clusters: Vec<Vec<CallId>>,
+    /// `relatedness.get(a.index(), b.index())`: the static relatedness terms
+    /// between calls `a` and `b` (see `RelatednessTerms`), precomputed so
+    /// `operators::removal::shaw_removal` can look up a pair's node
+    /// distances and time-window difference in O(1) instead of rescanning
+    /// compatible vehicles on every removal.
+    pub(super) relatedness: Matrix2<RelatednessTerms>,
+    /// `neighbor_order[a.index()]`: every other call paired with its
+    /// normalized pickup-node distance to `a` (`RelatednessTerms::pickup_distance`),
+    /// sorted ascending so the closest calls come first. This instance has
+    /// no node coordinates to build a geometric spatial index over (it's a
+    /// travel-cost/time matrix between nodes, not a coordinate space), so
+    /// "nearest calls" here means nearest by this existing precomputed
+    /// relatedness distance; see `Problem::nearest_calls`/`calls_within`.
+    pub(super) neighbor_order: Vec<Vec<(CallId, f32)>>,
 }
 
 impl ProblemIndex {
@@ -31,11 +76,261 @@ impl ProblemIndex {
         let pickup_tree = IntervalTree::new(pickup_windows);
         let delivery_tree = IntervalTree::new(delivery_windows);
 
+        let call_clusters = Self::cluster_calls(problem);
+
+        let relatedness = Self::compute_relatedness(problem);
+        let neighbor_order = Self::build_neighbor_order(&relatedness);
+
         ProblemIndex {
             cargo_vessel,
             pickup_tree,
             delivery_tree,
+            call_clusters,
+            relatedness,
+            neighbor_order,
+        }
+    }
+
+    /// Derives `neighbor_order` from the already-computed `relatedness`
+    /// matrix: for every call, every other call paired with its normalized
+    /// pickup-node distance, sorted ascending. Built once alongside the rest
+    /// of `ProblemIndex` and never touched again -- like `relatedness` and
+    /// `call_clusters`, it only depends on the static problem instance, not
+    /// on any solution's assignments, so unlike a coordinate-based spatial
+    /// index over moving vehicles, there's nothing here that a solution
+    /// mutation could ever invalidate.
+    fn build_neighbor_order(relatedness: &Matrix2<RelatednessTerms>) -> Vec<Vec<(CallId, f32)>> {
+        let n = relatedness.rows;
+
+        (0..n)
+            .map(|i| {
+                let mut neighbors: Vec<(CallId, f32)> = (0..n)
+                    .filter(|&j| j != i)
+                    .map(|j| (CallId::new_pickup((j + 1) as i16).unwrap(), relatedness.get(i, j).pickup_distance))
+                    .collect();
+
+                neighbors.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                neighbors
+            })
+            .collect()
+    }
+
+    /// Groups calls whose pickup nodes are mutually close into vicinity
+    /// clusters via single-linkage agglomerative clustering: every call
+    /// starts in its own cluster, and the two clusters with the smallest
+    /// minimum pairwise distance are repeatedly merged until no pair is
+    /// under `tau`. Pairwise distance is `d(a, b) = min over vehicles v
+    /// compatible with both a and b of travel_time[v][origin(a)][origin(b)]`
+    /// (`None`, i.e. infinitely far, if no vehicle serves both). `tau` is
+    /// the `CLUSTER_DISTANCE_PERCENTILE` percentile of every finite pairwise
+    /// distance, so it adapts across the differently-scaled instances the
+    /// CSV loader handles rather than being a fixed travel time.
+    fn cluster_calls(problem: &Problem) -> Vec<Vec<CallId>> {
+        let n = problem.n_calls.index() + 1;
+
+        let calls: Vec<CallId> = (1..=n).map(|i| CallId::new_pickup(i as i16).unwrap()).collect();
+
+        // All-pairs distances, `None` where no vehicle can serve both calls.
+        let mut distances = vec![vec![None; n]; n];
+        let mut finite_distances = Vec::new();
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if let Some(d) = Self::pair_distance(problem, calls[i], calls[j]) {
+                    distances[i][j] = Some(d);
+                    distances[j][i] = Some(d);
+                    finite_distances.push(d);
+                }
+            }
         }
+
+        if finite_distances.is_empty() {
+            return calls.into_iter().map(|c| vec![c]).collect();
+        }
+
+        finite_distances.sort_unstable();
+        let rank = ((finite_distances.len() - 1) as f64 * CLUSTER_DISTANCE_PERCENTILE).round() as usize;
+        let tau = finite_distances[rank];
+
+        let mut clusters: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+
+        loop {
+            let mut closest: Option<(Time, usize, usize)> = None;
+
+            for a in 0..clusters.len() {
+                for b in (a + 1)..clusters.len() {
+                    if let Some(d) = Self::cluster_distance(&distances, &clusters[a], &clusters[b]) {
+                        if closest.map_or(true, |(best_d, _, _)| d < best_d) {
+                            closest = Some((d, a, b));
+                        }
+                    }
+                }
+            }
+
+            match closest {
+                Some((d, a, b)) if d < tau => {
+                    let merged = clusters.swap_remove(b);
+                    clusters[a].extend(merged);
+                }
+                _ => break,
+            }
+        }
+
+        clusters
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| calls[i]).collect())
+            .collect()
+    }
+
+    /// `d(a, b)`: the minimum travel time, over every vehicle compatible
+    /// with both `a` and `b`, between their pickup nodes. `None` if no
+    /// vehicle serves both.
+    fn pair_distance(problem: &Problem, a: CallId, b: CallId) -> Option<Time> {
+        let vehicles_a = problem.get_compatible_vehicles(a);
+        let vehicles_b = problem.get_compatible_vehicles(b);
+
+        vehicles_a
+            .iter()
+            .filter(|v| vehicles_b.contains(v))
+            .map(|&v| problem.get_travel_time(v, problem.origin_node(a), problem.origin_node(b)))
+            .min()
+    }
+
+    /// Like `pair_distance`, but between `a` and `b`'s destination nodes
+    /// rather than their origin nodes.
+    fn destination_pair_distance(problem: &Problem, a: CallId, b: CallId) -> Option<Time> {
+        let vehicles_a = problem.get_compatible_vehicles(a);
+        let vehicles_b = problem.get_compatible_vehicles(b);
+
+        vehicles_a
+            .iter()
+            .filter(|v| vehicles_b.contains(v))
+            .map(|&v| problem.get_travel_time(v, problem.destination_node(a), problem.destination_node(b)))
+            .min()
+    }
+
+    /// Precomputes every pair of calls' `RelatednessTerms`: travel time
+    /// between pickup nodes, between delivery nodes, pickup time-window
+    /// start difference and cargo-size difference, each normalized to
+    /// `[0, 1]` by the largest such value seen across the instance so these
+    /// four terms are comparable regardless of the instance's scale, plus
+    /// the pair's vehicle-compatibility distance. Pairs with no common
+    /// compatible vehicle fall
+    /// back to the instance-wide maximum (i.e. maximally unrelated) rather
+    /// than `0.0`, which would otherwise read as maximally related.
+    fn compute_relatedness(problem: &Problem) -> Matrix2<RelatednessTerms> {
+        let n = problem.n_calls.index() + 1;
+        let calls: Vec<CallId> = (1..=n).map(|i| CallId::new_pickup(i as i16).unwrap()).collect();
+
+        let mut pickup_raw = vec![vec![0 as Time; n]; n];
+        let mut delivery_raw = vec![vec![0 as Time; n]; n];
+        let mut time_raw = vec![vec![0 as Time; n]; n];
+        let mut load_raw = vec![vec![0 as CargoSize; n]; n];
+        let mut compatibility_raw = vec![vec![0.0f32; n]; n];
+
+        let mut max_pickup: Time = 0;
+        let mut max_delivery: Time = 0;
+        let mut max_time: Time = 0;
+        let mut max_load: CargoSize = 0;
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let a = calls[i];
+                let b = calls[j];
+
+                let pickup_d = Self::pair_distance(problem, a, b).unwrap_or(Time::MAX);
+                let delivery_d = Self::destination_pair_distance(problem, a, b).unwrap_or(Time::MAX);
+                let time_d = (problem.pickup_time_window(a).start() - problem.pickup_time_window(b).start()).abs();
+                let load_d = problem.cargo_size(a).abs_diff(problem.cargo_size(b));
+                let compatibility_d = Self::compatibility_distance(problem, a, b);
+
+                pickup_raw[i][j] = pickup_d;
+                pickup_raw[j][i] = pickup_d;
+                delivery_raw[i][j] = delivery_d;
+                delivery_raw[j][i] = delivery_d;
+                time_raw[i][j] = time_d;
+                time_raw[j][i] = time_d;
+                load_raw[i][j] = load_d;
+                load_raw[j][i] = load_d;
+                compatibility_raw[i][j] = compatibility_d;
+                compatibility_raw[j][i] = compatibility_d;
+
+                if pickup_d != Time::MAX {
+                    max_pickup = max_pickup.max(pickup_d);
+                }
+                if delivery_d != Time::MAX {
+                    max_delivery = max_delivery.max(delivery_d);
+                }
+                max_time = max_time.max(time_d);
+                max_load = max_load.max(load_d);
+            }
+        }
+
+        let normalize = |value: Time, max: Time| -> f32 {
+            if value == Time::MAX {
+                1.0
+            } else if max == 0 {
+                0.0
+            } else {
+                value as f32 / max as f32
+            }
+        };
+
+        let normalize_load = |value: CargoSize, max: CargoSize| -> f32 {
+            if max == 0 {
+                0.0
+            } else {
+                value as f32 / max as f32
+            }
+        };
+
+        let mut relatedness = Matrix2::new(n, n, RelatednessTerms::default());
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+
+                *relatedness.get_mut(i, j) = RelatednessTerms {
+                    pickup_distance: normalize(pickup_raw[i][j], max_pickup),
+                    delivery_distance: normalize(delivery_raw[i][j], max_delivery),
+                    time_window_diff: normalize(time_raw[i][j], max_time),
+                    load_diff: normalize_load(load_raw[i][j], max_load),
+                    vehicle_compatibility: compatibility_raw[i][j],
+                };
+            }
+        }
+
+        relatedness
+    }
+
+    /// Fraction of `a` and `b`'s compatible vehicles that aren't shared by
+    /// both (`1.0` minus the intersection-over-union of their compatible-
+    /// vehicle sets), so two calls served by exactly the same vehicles score
+    /// `0.0` (most related) and two with no compatible vehicle in common
+    /// score `1.0` (least related). Both sets empty is treated as fully
+    /// unrelated rather than dividing by zero.
+    fn compatibility_distance(problem: &Problem, a: CallId, b: CallId) -> f32 {
+        let vehicles_a = problem.get_compatible_vehicles(a);
+        let vehicles_b = problem.get_compatible_vehicles(b);
+
+        let intersection = vehicles_a.iter().filter(|v| vehicles_b.contains(v)).count();
+        let union = vehicles_a.len() + vehicles_b.len() - intersection;
+
+        if union == 0 {
+            1.0
+        } else {
+            1.0 - (intersection as f32 / union as f32)
+        }
+    }
+
+    /// Single-linkage distance between two clusters (by 0-indexed call
+    /// index): the minimum `distances` entry between any member of `a` and
+    /// any member of `b`, or `None` if every such pair is infinitely far.
+    fn cluster_distance(distances: &[Vec<Option<Time>>], a: &[usize], b: &[usize]) -> Option<Time> {
+        a.iter()
+            .flat_map(|&i| b.iter().filter_map(move |&j| distances[i][j]))
+            .min()
     }
 
     /// Create a new cargo_vessel Vec<Vec> from the vessel_cargo matrix
@@ -0,0 +1,244 @@
+use crate::types::{CallId, VehicleId};
+
+/// Whether a locked call group's relative order (`Sequence`) must also be
+/// contiguous (`Strict`, i.e. the group's pickups occupy consecutive route
+/// positions, with no other stop between them).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockOrder {
+    Sequence,
+    Strict,
+}
+
+/// Where a locked call group must fall within its vehicle's route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockPosition {
+    Any,
+    First,
+    Last,
+}
+
+/// A single operational constraint pinning, forbidding, or ordering a call.
+/// Call IDs are always normalized to their pickup form, since a lock applies
+/// to the call as a whole rather than to one leg of it.
+#[derive(Clone, Debug)]
+enum Lock {
+    /// The call must be served by the given vehicle.
+    AssignTo(CallId, VehicleId),
+    /// The call may never be served by the given vehicle.
+    Forbid(CallId, VehicleId),
+    /// The call must be served by the given vehicle with its pickup and
+    /// delivery at exactly these route indices -- a pre-committed stop that
+    /// must not move at all during re-optimization.
+    Fixed(CallId, VehicleId, usize, usize),
+    /// `before`'s pickup must precede `after`'s pickup within their (shared) route.
+    SequenceBefore(CallId, CallId),
+    /// The call's pickup must be the first stop in its vehicle's route.
+    AnchorStart(CallId),
+    /// The call's delivery must be the last stop in its vehicle's route.
+    AnchorEnd(CallId),
+    /// An ordered group of calls that must all be served by `vehicle`, in
+    /// the given relative order and route position (as parsed from a
+    /// problem file's optional trailing locks section; see `Problem::load`).
+    Group {
+        vehicle: VehicleId,
+        calls: Vec<CallId>,
+        order: LockOrder,
+        position: LockPosition,
+    },
+}
+
+/// A set of locks declared on a `Problem`, consulted by `Solution::insert_call`,
+/// `FeasibleInsertions`, and `Solution::feasible` so operators can never produce
+/// a solution that violates an operational constraint (must-keep couriers,
+/// pre-committed pickups, fixed sequences). Modeled on vrp-core's locked-jobs
+/// feature. Build one with `LockBuilder` and attach it to `Problem::locks`.
+#[derive(Clone, Debug, Default)]
+pub struct LockSet {
+    locks: Vec<Lock>,
+}
+
+impl LockSet {
+    pub fn is_empty(&self) -> bool {
+        self.locks.is_empty()
+    }
+
+    /// Whether `vehicle` is allowed to serve `call`, considering any
+    /// assign-to/forbid locks declared for it.
+    pub fn is_vehicle_allowed(&self, call: CallId, vehicle: VehicleId) -> bool {
+        let call = call.pickup();
+
+        let assigned_to = self.locks.iter().find_map(|lock| match lock {
+            Lock::AssignTo(c, v) if *c == call => Some(*v),
+            Lock::Fixed(c, v, _, _) if *c == call => Some(*v),
+            Lock::Group { vehicle, calls, .. } if calls.contains(&call) => Some(*vehicle),
+            _ => None,
+        });
+
+        if let Some(required) = assigned_to {
+            return required == vehicle;
+        }
+
+        !self.locks.iter().any(|lock| matches!(lock, Lock::Forbid(c, v) if *c == call && *v == vehicle))
+    }
+
+    /// Returns `Some((vehicle, pickup_idx, delivery_idx))` if `call` is
+    /// pinned to an exact route position via a `Fixed` lock.
+    pub fn fixed_position(&self, call: CallId) -> Option<(VehicleId, usize, usize)> {
+        let call = call.pickup();
+        self.locks.iter().find_map(|lock| match lock {
+            Lock::Fixed(c, v, pickup_idx, delivery_idx) if *c == call => Some((*v, *pickup_idx, *delivery_idx)),
+            _ => None,
+        })
+    }
+
+    pub fn is_anchored_start(&self, call: CallId) -> bool {
+        let call = call.pickup();
+        self.locks.iter().any(|lock| matches!(lock, Lock::AnchorStart(c) if *c == call))
+    }
+
+    pub fn is_anchored_end(&self, call: CallId) -> bool {
+        let call = call.pickup();
+        self.locks.iter().any(|lock| matches!(lock, Lock::AnchorEnd(c) if *c == call))
+    }
+
+    /// Returns `true` if placing `call`'s pickup at `pickup_idx` in a route
+    /// currently holding `route_calls` would violate a sequence-before lock.
+    pub fn sequence_violation(&self, route_calls: &[CallId], call: CallId, pickup_idx: usize) -> bool {
+        let call = call.pickup();
+
+        for lock in &self.locks {
+            match lock {
+                Lock::SequenceBefore(before, after) if *after == call => {
+                    match route_calls.iter().position(|c| c.pickup() == *before) {
+                        Some(pos) if pos < pickup_idx => {}
+                        _ => return true,
+                    }
+                }
+                Lock::SequenceBefore(before, after) if *before == call => {
+                    if let Some(pos) = route_calls.iter().position(|c| c.pickup() == *after) {
+                        if pos < pickup_idx {
+                            return true;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    /// Returns `true` if `vehicle`'s `route_calls` violates a locked group
+    /// assigned to it: a group member missing from the route, the group's
+    /// members out of their required relative order, not contiguous under
+    /// `LockOrder::Strict`, or not at the route boundary required by
+    /// `LockPosition::First`/`Last`.
+    pub fn group_violation(&self, vehicle: VehicleId, route_calls: &[CallId]) -> bool {
+        for lock in &self.locks {
+            let Lock::Group { vehicle: group_vehicle, calls, order, position } = lock else {
+                continue;
+            };
+            if *group_vehicle != vehicle {
+                continue;
+            }
+
+            let positions: Option<Vec<usize>> = calls
+                .iter()
+                .map(|&call| route_calls.iter().position(|c| c.pickup() == call))
+                .collect();
+
+            let Some(positions) = positions else {
+                return true;
+            };
+
+            if !positions.windows(2).all(|w| w[0] < w[1]) {
+                return true;
+            }
+
+            if *order == LockOrder::Strict {
+                let span = positions[positions.len() - 1] - positions[0];
+                if span != positions.len() - 1 {
+                    return true;
+                }
+            }
+
+            match position {
+                LockPosition::First if positions[0] != 0 => return true,
+                LockPosition::Last => {
+                    let last_call = *calls.last().expect("a lock group is never empty");
+                    match route_calls.iter().rposition(|&c| c == last_call.delivery()) {
+                        Some(delivery_pos) if delivery_pos == route_calls.len() - 1 => {}
+                        _ => return true,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
+}
+
+/// Builder for declaring locks on a `Problem`. Collects constraints and
+/// produces an immutable `LockSet` via `build`.
+#[derive(Default)]
+pub struct LockBuilder {
+    locks: Vec<Lock>,
+}
+
+impl LockBuilder {
+    pub fn new() -> Self {
+        LockBuilder::default()
+    }
+
+    /// Pins `call` to `vehicle`: no other vehicle may serve it.
+    pub fn assign_to(mut self, call: CallId, vehicle: VehicleId) -> Self {
+        self.locks.push(Lock::AssignTo(call.pickup(), vehicle));
+        self
+    }
+
+    /// Forbids `vehicle` from ever serving `call`.
+    pub fn forbid(mut self, call: CallId, vehicle: VehicleId) -> Self {
+        self.locks.push(Lock::Forbid(call.pickup(), vehicle));
+        self
+    }
+
+    /// Pins `call` to `vehicle` at the exact given pickup/delivery route
+    /// indices, e.g. a pre-committed stop that must not move at all during
+    /// re-optimization.
+    pub fn fix(mut self, call: CallId, vehicle: VehicleId, pickup_idx: usize, delivery_idx: usize) -> Self {
+        self.locks.push(Lock::Fixed(call.pickup(), vehicle, pickup_idx, delivery_idx));
+        self
+    }
+
+    /// Requires `before`'s pickup to precede `after`'s pickup in their route.
+    pub fn sequence_before(mut self, before: CallId, after: CallId) -> Self {
+        self.locks.push(Lock::SequenceBefore(before.pickup(), after.pickup()));
+        self
+    }
+
+    /// Requires `call`'s pickup to be the first stop in its vehicle's route.
+    pub fn anchor_start(mut self, call: CallId) -> Self {
+        self.locks.push(Lock::AnchorStart(call.pickup()));
+        self
+    }
+
+    /// Requires `call`'s delivery to be the last stop in its vehicle's route.
+    pub fn anchor_end(mut self, call: CallId) -> Self {
+        self.locks.push(Lock::AnchorEnd(call.pickup()));
+        self
+    }
+
+    /// Pins an ordered group of `calls` to `vehicle`, required to appear in
+    /// that relative order (and contiguously, under `LockOrder::Strict`),
+    /// and at the route boundary required by `position`.
+    pub fn group(mut self, vehicle: VehicleId, calls: Vec<CallId>, order: LockOrder, position: LockPosition) -> Self {
+        let calls = calls.into_iter().map(CallId::pickup).collect();
+        self.locks.push(Lock::Group { vehicle, calls, order, position });
+        self
+    }
+
+    pub fn build(self) -> LockSet {
+        LockSet { locks: self.locks }
+    }
+}
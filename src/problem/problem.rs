@@ -1,4 +1,8 @@
+use crate::problem::cache;
+use crate::problem::clustering::{ClusterMap, ClusteringPolicy};
+use crate::problem::dimension::Dimension;
 use crate::problem::index::ProblemIndex;
+use crate::problem::locks::{LockBuilder, LockOrder, LockPosition, LockSet};
 use crate::types::*;
 use crate::utils::*;
 use std::fs::File;
@@ -39,6 +43,31 @@ pub enum Cargo {
     CostOfNotTransporting,
 }
 
+/// Selects how `Route::simulate` treats a call arriving outside its time
+/// window. `Hard` (the default) aborts the route at the first violation, as
+/// it always has. `Soft` instead keeps simulating to completion and
+/// accumulates a cost into `SimulationResult::soft_penalty`, proportional to
+/// how far outside the window the arrival fell, so the optimizer can rank
+/// near-feasible neighborhoods instead of discarding them outright.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeWindowPolicy {
+    Hard,
+    Soft {
+        /// Cost charged per unit of time arriving after `time_window.end()`.
+        late_penalty: Cost,
+        /// Cost charged per unit of time arriving before `time_window.start()`,
+        /// in place of the vehicle waiting for the window to open. `None`
+        /// keeps the forced wait.
+        early_penalty: Option<Cost>,
+    },
+}
+
+impl Default for TimeWindowPolicy {
+    fn default() -> Self {
+        TimeWindowPolicy::Hard
+    }
+}
+
 /// The main problem data structure.
 pub struct Problem {
     /// Number of nodes (always 39).
@@ -66,11 +95,60 @@ pub struct Problem {
     pub port_cost: Matrix2<Cost>,
     /// Precomputed data structures.
     pub index: ProblemIndex,
+    /// Operational constraints (pinned vehicles, forbidden vehicles, fixed
+    /// sequences/anchors, locked call groups) that operators must never
+    /// violate. Populated from the problem file's optional trailing locks
+    /// section (see `Problem::load`); empty unless that section is present
+    /// or the caller adds more after loading.
+    pub locks: LockSet,
+    /// Whether a route's schedule includes an explicit leg back to the
+    /// vehicle's `home_node` after its last stop. Off by default (the data
+    /// files have no notion of it); set by the caller to close the cost
+    /// model and enable the minimize-arrival-time objective (see
+    /// `Solution::completion_time`).
+    pub return_to_depot: bool,
+    /// Capacity dimensions beyond the legacy weight dimension (`CallParameters::size`
+    /// / `Vehicle::capacity`), e.g. volume or pallet count. Empty unless
+    /// populated by the caller; `Route::simulate` and `find_spare_capacity`
+    /// enforce every dimension's bound alongside weight.
+    pub dimensions: Vec<Dimension>,
+    /// How `Route::simulate` treats a time-window violation. Hard (the
+    /// long-standing default) unless the caller opts into soft penalties.
+    pub time_window_policy: TimeWindowPolicy,
 }
 
 impl Problem {
-    /// Loads a problem from a CSV file.
+    /// Loads a problem from a CSV file via `load_with_cache` with the default
+    /// cache directory: its `ProblemIndex` is read back from an on-disk
+    /// cache entry for this instance when one exists, and built and written
+    /// there otherwise.
     pub fn load(filename: &str) -> Result<Self, String> {
+        Self::load_with_cache(filename, None, false)
+    }
+
+    /// Like `load`, but transparently caches the parsed `ProblemIndex` on
+    /// disk under `cache_dir` (defaulting to `cache::DEFAULT_CACHE_DIR`),
+    /// keyed by a content hash of `filename`'s raw bytes. `force_recompute`
+    /// skips reading back an existing cache entry (a fresh one is still
+    /// written), for instances whose contents changed without also
+    /// changing their path. See `problem::cache::load_or_build_index`.
+    pub fn load_with_cache(filename: &str, cache_dir: Option<&str>, force_recompute: bool) -> Result<Self, String> {
+        let raw_bytes = std::fs::read(filename).map_err(|e| format!("File not found: {}", e))?;
+
+        let mut problem = Self::parse(filename)?;
+
+        let cache_dir = cache_dir.map(std::path::PathBuf::from).unwrap_or_else(|| std::path::PathBuf::from(cache::DEFAULT_CACHE_DIR));
+        problem.index = cache::load_or_build_index(&problem, &raw_bytes, &cache_dir, force_recompute);
+
+        Ok(problem)
+    }
+
+    /// Parses a problem from a CSV file, leaving its `ProblemIndex` as
+    /// `ProblemIndex::default()` -- `load_with_cache` is the only caller,
+    /// and fills it in afterward via `cache::load_or_build_index` so a
+    /// cache hit never pays for a parse-time rebuild that's about to be
+    /// discarded.
+    fn parse(filename: &str) -> Result<Self, String> {
         let file = File::open(filename).map_err(|e| format!("File not found: {}", e))?;
         let reader = BufReader::new(file);
         let mut lines = reader
@@ -210,12 +288,55 @@ impl Problem {
             });
         }
 
-        // Read travel times and costs
+        // Read travel times and costs. The section is normally dense (every
+        // vehicle/origin/destination triple listed explicitly), but a
+        // leading bare count line instead puts it in sparse mode: only that
+        // many arcs follow, and every unlisted `[v][o][d]` is completed by
+        // per-vehicle all-pairs shortest paths (see `complete_sparse_travel`)
+        // once every given arc has been read.
         let total_travel_entries = num_vehicles * num_nodes * num_nodes;
+        let infinite_time = Time::MAX;
+        let infinite_cost = Cost::MAX;
         let mut travel_time = Matrix3::new(num_vehicles, num_nodes, num_nodes, 0 as Time);
         let mut travel_cost = Matrix3::new(num_vehicles, num_nodes, num_nodes, 0 as Cost);
-        for _ in 0..total_travel_entries {
-            let line = lines.next().ok_or("Missing travel time/cost")??;
+
+        let first_travel_line = lines.next().ok_or("Missing travel time/cost")??;
+        let sparse = first_travel_line.split(',').count() == 1;
+
+        let travel_entries: usize = if sparse {
+            first_travel_line
+                .trim()
+                .parse()
+                .map_err(|e| format!("Bad sparse travel arc count: {}", e))?
+        } else {
+            total_travel_entries
+        };
+
+        if sparse {
+            for v in 0..num_vehicles {
+                for n in 0..num_nodes {
+                    *travel_time.get_mut(v, n, n) = 0;
+                    *travel_cost.get_mut(v, n, n) = 0;
+                }
+            }
+            for v in 0..num_vehicles {
+                for o in 0..num_nodes {
+                    for d in 0..num_nodes {
+                        if o != d {
+                            *travel_time.get_mut(v, o, d) = infinite_time;
+                            *travel_cost.get_mut(v, o, d) = infinite_cost;
+                        }
+                    }
+                }
+            }
+        }
+
+        for i in 0..travel_entries {
+            let line = if i == 0 && !sparse {
+                first_travel_line.clone()
+            } else {
+                lines.next().ok_or("Missing travel time/cost")??
+            };
             let parts: Vec<&str> = line.split(',').collect();
             if parts.len() < 5 {
                 return Err("Travel time/cost line has insufficient parts".into());
@@ -254,6 +375,17 @@ impl Problem {
             *travel_cost.get_mut(v, o, d) = cost;
         }
 
+        if sparse {
+            Self::complete_sparse_travel(
+                num_vehicles,
+                num_nodes,
+                &mut travel_time,
+                &mut travel_cost,
+                infinite_time,
+                infinite_cost,
+            )?;
+        }
+
         // Read node times/costs
         let total_node_entries = num_vehicles * num_calls;
         let mut loading_time = Matrix2::new(num_vehicles, num_calls, 0 as Time);
@@ -301,6 +433,54 @@ impl Problem {
             *port_cost.get_mut(v, c) = origin_cost + destination_cost;
         }
 
+        // Optional trailing locks section: each remaining line declares a
+        // group of calls pinned to one vehicle, as
+        // `vehicle,call[,call...],order,position` with `order` one of
+        // Sequence/Strict and `position` one of Any/First/Last.
+        let mut locks = LockBuilder::new();
+        for line in lines {
+            let line = line?;
+            let parts: Vec<&str> = line.split(',').collect();
+            if parts.len() < 4 {
+                return Err("Lock line has insufficient parts".into());
+            }
+
+            let vehicle_raw: u8 = parts[0]
+                .trim()
+                .parse()
+                .map_err(|e| format!("Bad vehicle index in lock: {}", e))?;
+            let vehicle = VehicleId::new(vehicle_raw).ok_or("Vehicle index underflow in lock")?;
+
+            let order = match parts[parts.len() - 2].trim() {
+                "Sequence" => LockOrder::Sequence,
+                "Strict" => LockOrder::Strict,
+                other => return Err(format!("Bad lock order flag: {}", other)),
+            };
+            let position = match parts[parts.len() - 1].trim() {
+                "Any" => LockPosition::Any,
+                "First" => LockPosition::First,
+                "Last" => LockPosition::Last,
+                other => return Err(format!("Bad lock position flag: {}", other)),
+            };
+
+            let calls: Vec<CallId> = parts[1..parts.len() - 2]
+                .iter()
+                .map(|part| {
+                    let call_raw: i16 = part
+                        .trim()
+                        .parse()
+                        .map_err(|e| format!("Bad call id in lock: {}", e))?;
+                    CallId::new_pickup(call_raw).ok_or_else(|| "Call id must be positive in lock".to_string())
+                })
+                .collect::<Result<_, String>>()?;
+
+            if calls.is_empty() {
+                return Err("Lock line declares no calls".into());
+            }
+
+            locks = locks.group(vehicle, calls, order, position);
+        }
+
         let mut problem = Problem {
             n_nodes: num_nodes
                 .try_into()
@@ -324,13 +504,129 @@ impl Problem {
             unloading_time,
             port_cost,
             index: ProblemIndex::default(),
+            locks: LockSet::default(),
+            return_to_depot: false,
+            dimensions: Vec::new(),
+            time_window_policy: TimeWindowPolicy::default(),
         };
 
-        problem.index = ProblemIndex::new(&problem);
+        problem.locks = locks.build();
 
         Ok(problem)
     }
 
+    /// Completes a sparse travel section read by `load`: for each vehicle
+    /// independently, runs Floyd-Warshall over `travel_time` (carrying the
+    /// matching `travel_cost` along the same relaxed path, so cost stays
+    /// consistent with the time-minimal route) to fill in every arc that
+    /// wasn't explicitly given. Fails if any node pair is still unreachable
+    /// (its time sentinel still `infinite_time`) once every intermediate
+    /// node has been tried.
+    fn complete_sparse_travel(
+        num_vehicles: usize,
+        num_nodes: usize,
+        travel_time: &mut Matrix3<Time>,
+        travel_cost: &mut Matrix3<Cost>,
+        infinite_time: Time,
+        infinite_cost: Cost,
+    ) -> Result<(), String> {
+        for v in 0..num_vehicles {
+            for k in 0..num_nodes {
+                for i in 0..num_nodes {
+                    let dik = *travel_time.get(v, i, k);
+                    if dik == infinite_time {
+                        continue;
+                    }
+                    for j in 0..num_nodes {
+                        let dkj = *travel_time.get(v, k, j);
+                        if dkj == infinite_time {
+                            continue;
+                        }
+                        let via = dik + dkj;
+                        if via < *travel_time.get(v, i, j) {
+                            *travel_time.get_mut(v, i, j) = via;
+                            *travel_cost.get_mut(v, i, j) = *travel_cost.get(v, i, k) + *travel_cost.get(v, k, j);
+                        }
+                    }
+                }
+            }
+        }
+
+        for v in 0..num_vehicles {
+            for i in 0..num_nodes {
+                for j in 0..num_nodes {
+                    if *travel_time.get(v, i, j) == infinite_time || *travel_cost.get(v, i, j) == infinite_cost {
+                        return Err(format!(
+                            "Sparse travel data leaves vehicle {} node pair ({}, {}) unreachable",
+                            v + 1,
+                            i + 1,
+                            j + 1
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds a minimal problem for unit tests: a single node, zero travel
+    /// times/costs, and effectively unlimited per-vehicle capacity. Lets
+    /// tests exercise insertion/removal logic without a data file.
+    #[cfg(test)]
+    pub(crate) fn minimal(n_vehicles: usize, n_calls: usize) -> Self {
+        let vehicles = (0..n_vehicles)
+            .map(|_| Vehicle {
+                home_node: 0,
+                starting_time: 0,
+                capacity: 1_000_000,
+            })
+            .collect();
+
+        let calls = (0..n_calls)
+            .map(|_| CallParameters {
+                origin: 0,
+                destination: 0,
+                size: 1,
+                not_transport_cost: 0,
+                pickup_window: 0..=1_000,
+                delivery_window: 0..=1_000,
+            })
+            .collect();
+
+        let mut problem = Problem {
+            n_nodes: 1,
+            n_vehicles: VehicleId::new(n_vehicles as u8).unwrap(),
+            n_calls: CallId::new_pickup(n_calls as i16).unwrap(),
+            vehicles,
+            calls,
+            travel_time: Matrix3::new(n_vehicles, 1, 1, 0),
+            travel_cost: Matrix3::new(n_vehicles, 1, 1, 0),
+            vessel_cargo: Matrix2::new(n_vehicles, n_calls, true),
+            loading_time: Matrix2::new(n_vehicles, n_calls, 0),
+            unloading_time: Matrix2::new(n_vehicles, n_calls, 0),
+            port_cost: Matrix2::new(n_vehicles, n_calls, 0),
+            index: ProblemIndex::default(),
+            locks: LockSet::default(),
+            return_to_depot: false,
+            dimensions: Vec::new(),
+            time_window_policy: TimeWindowPolicy::default(),
+        };
+        problem.index = ProblemIndex::new(&problem);
+        problem
+    }
+
+    /// Runs the vicinity-clustering preprocessing pass described by `policy`
+    /// (as in vrp-pragmatic's clustering): groups of calls whose pickup and
+    /// delivery locations are mutually close and whose time windows
+    /// sufficiently overlap are merged into a single composite "cluster
+    /// call". Returns the reduced problem the search should operate on,
+    /// along with the `ClusterMap` needed to expand its routes back to the
+    /// original call ids once a solution is found.
+    pub fn cluster(&self, policy: &ClusteringPolicy) -> (Problem, ClusterMap) {
+        crate::problem::clustering::build(self, policy)
+    }
+
     /// Returns the travel time for a given vehicle and node pair.
     pub fn get_travel_time(&self, vehicle: VehicleId, origin: NodeId, destination: NodeId) -> Time {
         *self
@@ -425,6 +721,52 @@ impl Problem {
         &self.index.cargo_vessel[call.index()]
     }
 
+    /// Returns the precomputed vicinity clusters of mutually close calls
+    /// (single-linkage agglomerative clustering over pickup-node travel
+    /// time; see `ProblemIndex::cluster_calls`), so construction heuristics
+    /// can insert a whole neighborhood of calls cheaply rather than one call
+    /// at a time.
+    pub fn call_clusters(&self) -> &[Vec<CallId>] {
+        &self.index.call_clusters
+    }
+
+    /// Returns the precomputed static relatedness terms between two distinct
+    /// calls: normalized travel time between their pickup nodes, between
+    /// their delivery nodes, normalized pickup time-window start difference,
+    /// normalized cargo-size difference, and compatible-vehicle-set distance
+    /// (see `problem::index::RelatednessTerms`). Used by
+    /// `operators::removal::shaw_removal` to weigh these together with the
+    /// current solution's same-vehicle indicator.
+    pub fn relatedness_terms(&self, a: CallId, b: CallId) -> (f32, f32, f32, f32, f32) {
+        let terms = self.index.relatedness.get(a.index(), b.index());
+        (
+            terms.pickup_distance,
+            terms.delivery_distance,
+            terms.time_window_diff,
+            terms.load_diff,
+            terms.vehicle_compatibility,
+        )
+    }
+
+    /// Returns up to `k` calls nearest to `call`, closest-first, by the same
+    /// normalized pickup-node distance `relatedness_terms` exposes as its
+    /// first term. Backed by `ProblemIndex::neighbor_order`, precomputed
+    /// once per instance, so this is an O(k) slice of an already-sorted list
+    /// rather than scanning and sorting every other call per query.
+    pub fn nearest_calls(&self, call: CallId, k: usize) -> &[(CallId, f32)] {
+        let neighbors = &self.index.neighbor_order[call.index()];
+        &neighbors[..k.min(neighbors.len())]
+    }
+
+    /// Returns every call within `max_distance` of `call` by the same
+    /// precomputed distance `nearest_calls` uses, found via a binary search
+    /// over the sorted neighbour order rather than scanning every call.
+    pub fn calls_within(&self, call: CallId, max_distance: f32) -> &[(CallId, f32)] {
+        let neighbors = &self.index.neighbor_order[call.index()];
+        let cut = neighbors.partition_point(|&(_, distance)| distance <= max_distance);
+        &neighbors[..cut]
+    }
+
     /// Returns the origin node for the given call.
     #[inline(always)]
     pub fn origin_node(&self, call: CallId) -> NodeId {
@@ -559,3 +901,59 @@ impl Problem {
         self.get_travel_cost(vehicle, origin_node, destination_node)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `complete_sparse_travel` is private to this module (it's only ever
+    // called from `Problem::load`'s sparse-travel branch), so its tests
+    // live here instead of in `solution/tests.rs`.
+
+    #[test]
+    fn complete_sparse_travel_fills_in_missing_arcs() {
+        let infinite_time = Time::MAX;
+        let infinite_cost = Cost::MAX;
+        let mut travel_time = Matrix3::new(1, 3, 3, infinite_time);
+        let mut travel_cost = Matrix3::new(1, 3, 3, infinite_cost);
+
+        for n in 0..3 {
+            *travel_time.get_mut(0, n, n) = 0;
+            *travel_cost.get_mut(0, n, n) = 0;
+        }
+        // Only 0->1 and 1->2 are given; 0->2 must be completed via node 1.
+        *travel_time.get_mut(0, 0, 1) = 5;
+        *travel_cost.get_mut(0, 0, 1) = 50;
+        *travel_time.get_mut(0, 1, 2) = 7;
+        *travel_cost.get_mut(0, 1, 2) = 70;
+
+        Problem::complete_sparse_travel(1, 3, &mut travel_time, &mut travel_cost, infinite_time, infinite_cost)
+            .expect("every pair is reachable via node 1");
+
+        assert_eq!(*travel_time.get(0, 0, 2), 12);
+        assert_eq!(*travel_cost.get(0, 0, 2), 120);
+    }
+
+    #[test]
+    fn complete_sparse_travel_reports_unreachable_pair() {
+        let infinite_time = Time::MAX;
+        let infinite_cost = Cost::MAX;
+        let mut travel_time = Matrix3::new(1, 3, 3, infinite_time);
+        let mut travel_cost = Matrix3::new(1, 3, 3, infinite_cost);
+
+        for n in 0..3 {
+            *travel_time.get_mut(0, n, n) = 0;
+            *travel_cost.get_mut(0, n, n) = 0;
+        }
+        // Node 2 has no incoming or outgoing arc at all, so it stays
+        // unreachable from (and to) every other node no matter which
+        // intermediate node Floyd-Warshall tries.
+        *travel_time.get_mut(0, 0, 1) = 5;
+        *travel_cost.get_mut(0, 0, 1) = 50;
+
+        let err = Problem::complete_sparse_travel(1, 3, &mut travel_time, &mut travel_cost, infinite_time, infinite_cost)
+            .expect_err("node 2 is unreachable and should be reported");
+
+        assert!(err.contains("unreachable"));
+    }
+}
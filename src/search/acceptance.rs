@@ -0,0 +1,179 @@
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::types::Cost;
+
+/// A pluggable acceptance criterion for the main annealing loop: decides
+/// whether a candidate move is adopted as the new incumbent.
+///
+/// `delta` is `candidate_cost - incumbent_cost`; `incumbent_cost`/`best_cost`
+/// are given so criteria like Record-to-Record Travel can compare against the
+/// best-known solution rather than just the current incumbent.
+pub trait Acceptance: Send {
+    fn accept(&mut self, delta: Cost, incumbent_cost: Cost, best_cost: Cost, rng: &mut ThreadRng) -> bool;
+
+    /// Calibrates internal parameters from the warm-up phase's observed average
+    /// worsening move (`delta_avg`), the caller's `final_temp` target, and the
+    /// number of iterations left in the main loop. Strategies with no notion of
+    /// a cooling schedule can ignore this.
+    fn calibrate(&mut self, _delta_avg: f32, _final_temp: f32, _remaining_iters: usize) {}
+
+    /// The criterion's current decaying parameter (temperature or threshold),
+    /// if it has one, for instrumentation purposes.
+    fn temperature(&self) -> Option<f32> {
+        None
+    }
+}
+
+/// Classic Metropolis acceptance: accepts improving moves unconditionally, and
+/// worsening moves with probability `exp(-delta / temp)`, with `temp` cooling
+/// geometrically toward `final_temp` over the main loop.
+pub struct Metropolis {
+    temp: f32,
+    alpha: f32,
+}
+
+impl Metropolis {
+    pub fn new() -> Self {
+        Metropolis { temp: 1.0, alpha: 1.0 }
+    }
+}
+
+impl Default for Metropolis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Acceptance for Metropolis {
+    fn accept(&mut self, delta: Cost, _incumbent_cost: Cost, _best_cost: Cost, rng: &mut ThreadRng) -> bool {
+        let accept = delta < 0 || rng.random_bool(f32::exp(-delta as f32 / self.temp) as f64);
+        self.temp *= self.alpha;
+        accept
+    }
+
+    fn calibrate(&mut self, delta_avg: f32, final_temp: f32, remaining_iters: usize) {
+        self.temp = -delta_avg / f32::ln(0.8);
+        self.alpha = (final_temp / self.temp).powf(1.0 / remaining_iters.max(1) as f32);
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        Some(self.temp)
+    }
+}
+
+/// Late Acceptance Hill Climbing: keeps a circular history of the last `L`
+/// current-solution costs and accepts a candidate if it's no worse than the
+/// incumbent, or no worse than the cost recorded `L` iterations ago. Needs no
+/// temperature tuning and often outperforms SA on routing problems.
+pub struct LateAcceptanceHillClimbing {
+    history: Vec<Cost>,
+    cursor: usize,
+}
+
+impl LateAcceptanceHillClimbing {
+    pub fn new(history_length: usize, initial_cost: Cost) -> Self {
+        LateAcceptanceHillClimbing {
+            history: vec![initial_cost; history_length.max(1)],
+            cursor: 0,
+        }
+    }
+}
+
+impl Acceptance for LateAcceptanceHillClimbing {
+    fn accept(&mut self, delta: Cost, incumbent_cost: Cost, _best_cost: Cost, _rng: &mut ThreadRng) -> bool {
+        let candidate_cost = incumbent_cost + delta;
+        let history_cost = self.history[self.cursor];
+
+        let accept = candidate_cost <= incumbent_cost || candidate_cost <= history_cost;
+
+        self.history[self.cursor] = if accept { candidate_cost } else { incumbent_cost };
+        self.cursor = (self.cursor + 1) % self.history.len();
+
+        accept
+    }
+}
+
+/// Record-to-Record Travel: accepts any candidate within `deviation` of the
+/// best-known cost, regardless of the current incumbent.
+pub struct RecordToRecordTravel {
+    deviation: Cost,
+}
+
+impl RecordToRecordTravel {
+    pub fn new(deviation: Cost) -> Self {
+        RecordToRecordTravel { deviation }
+    }
+}
+
+impl Acceptance for RecordToRecordTravel {
+    fn accept(&mut self, delta: Cost, incumbent_cost: Cost, best_cost: Cost, _rng: &mut ThreadRng) -> bool {
+        let candidate_cost = incumbent_cost + delta;
+        candidate_cost < best_cost + self.deviation
+    }
+}
+
+/// Threshold accepting: a deterministic cousin of Metropolis that accepts any
+/// move worsening the incumbent by no more than a threshold, shrinking the
+/// threshold geometrically toward (near-)zero over the main loop.
+pub struct ThresholdAccepting {
+    threshold: f32,
+    alpha: f32,
+}
+
+impl ThresholdAccepting {
+    pub fn new() -> Self {
+        ThresholdAccepting { threshold: 1.0, alpha: 1.0 }
+    }
+}
+
+impl Default for ThresholdAccepting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Acceptance for ThresholdAccepting {
+    fn accept(&mut self, delta: Cost, _incumbent_cost: Cost, _best_cost: Cost, _rng: &mut ThreadRng) -> bool {
+        let accept = (delta as f32) <= self.threshold;
+        self.threshold *= self.alpha;
+        accept
+    }
+
+    fn calibrate(&mut self, delta_avg: f32, _final_temp: f32, remaining_iters: usize) {
+        self.threshold = delta_avg.max(1.0);
+        self.alpha = (0.01_f32 / self.threshold).powf(1.0 / remaining_iters.max(1) as f32);
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        Some(self.threshold)
+    }
+}
+
+/// Selects which `Acceptance` strategy the search entry point should build for
+/// each chain; kept separate from `Acceptance` itself so it can be `Copy` and
+/// passed into parallel islands, each of which builds its own instance.
+#[derive(Clone, Copy, Debug)]
+pub enum AcceptanceStrategy {
+    Metropolis,
+    LateAcceptanceHillClimbing { history_length: usize },
+    RecordToRecordTravel { deviation: Cost },
+    ThresholdAccepting,
+}
+
+impl AcceptanceStrategy {
+    /// Builds a fresh `Acceptance` instance, seeded with `initial_cost` where a
+    /// strategy needs a starting point for its history (LAHC).
+    pub fn build(&self, initial_cost: Cost) -> Box<dyn Acceptance> {
+        match *self {
+            AcceptanceStrategy::Metropolis => Box::new(Metropolis::new()),
+            AcceptanceStrategy::LateAcceptanceHillClimbing { history_length } => {
+                Box::new(LateAcceptanceHillClimbing::new(history_length, initial_cost))
+            }
+            AcceptanceStrategy::RecordToRecordTravel { deviation } => {
+                Box::new(RecordToRecordTravel::new(deviation))
+            }
+            AcceptanceStrategy::ThresholdAccepting => Box::new(ThresholdAccepting::new()),
+        }
+    }
+}
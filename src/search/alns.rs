@@ -1,15 +1,34 @@
 use crate::problem::Problem;
 use crate::solution::Solution;
 use crate::operators::params::RemovalParams;
-use crate::operators::mutate::PARAMS as REMOVAL_PARAMS;
-use crate::metrics::IterationRecord;
+use crate::operators::mutate::{PARAMS as REMOVAL_PARAMS, REMOVAL_OPERATORS};
+use crate::operators::INSERTION_OPERATORS;
+use crate::metrics::{AcceptanceOutcome, IterationRecord, MetricsWriter};
 use crate::search::progress::SearchProgress;
 use crate::types::{CallId, Cost, OperatorPair};
 
 use rand::distr::weighted::WeightedIndex;
 use rand::prelude::*;
+use rand::rngs::SmallRng;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Instant;
+use crate::operators::local_search;
+use crate::operators::insertion;
 use crate::operators::removal::random_calls;
+use crate::solution::objectives::{self, N_OBJECTIVES};
+
+/// Upper bound on `ALNS::pareto_archive`'s size. Because its objectives vary
+/// continuously run to run, almost every evaluated candidate lands mutually
+/// non-dominated with what's already archived, so without a cap the archive
+/// (and the O(n) dominance scan `update_pareto_archive` does per insert)
+/// would grow roughly linearly with iteration count over what can be a
+/// multi-hour run (see `IslandParams`/`run_islands`'s deadline). Once full,
+/// the least diverse entry is evicted (see `evict_least_crowded`) to make
+/// room for the new one.
+const PARETO_ARCHIVE_CAPACITY: usize = 200;
 
 #[derive(Copy, Clone)]
 pub struct ScoreParams {
@@ -18,6 +37,50 @@ pub struct ScoreParams {
     pub novelty: f32,
 }
 
+/// Parameters for the parallel multi-start driver `run_islands`: how many
+/// independent ALNS workers to run side by side, how often they check in
+/// with the shared leader board, and how readily a stagnating worker adopts
+/// the shared global best.
+///
+/// Unlike `search::annealing::IslandParams`/`search::pooled`'s island model,
+/// this has no `topology` field: `run_islands` gives every worker the same
+/// single shared board (the `FullyConnected` case there), since its
+/// `Migration` is one `Arc<Mutex<(Cost, Solution)>>` rather than a
+/// per-island leader board `Ring` can wire up predecessor-to-successor.
+/// Adding `Ring` here would mean rebuilding `Migration` around the same
+/// `Vec<Arc<Mutex<LeaderBoard>>>` shape `pooled::run_islands` uses, which is
+/// out of scope for exposing the existing migration knobs via CLI.
+#[derive(Clone, Copy, Debug)]
+pub struct IslandParams {
+    /// Number of independent ALNS workers. `1` takes the single-thread path.
+    pub n_workers: usize,
+    /// Iterations between a worker's check-ins with the shared leader board.
+    pub migration_interval: usize,
+    /// A worker only pulls the global best into its incumbent if its own
+    /// `best_cost` is worse by more than this absolute gap.
+    pub migration_gap: Cost,
+}
+
+impl Default for IslandParams {
+    fn default() -> Self {
+        IslandParams {
+            n_workers: 1,
+            migration_interval: 100,
+            migration_gap: 0,
+        }
+    }
+}
+
+/// Handle an ALNS worker uses to check in with the shared leader board in
+/// `run_islands`: every `interval` iterations it posts its best
+/// `(Cost, Solution)` if that improves on the global best, and pulls the
+/// global best into its own incumbent if its best is worse by more than `gap`.
+struct Migration {
+    board: Arc<Mutex<(Cost, Solution)>>,
+    interval: usize,
+    gap: Cost,
+}
+
 pub struct ALNS<'a> {
     operator_combinations: &'a [OperatorPair],
     weights: Vec<f32>,
@@ -29,6 +92,24 @@ pub struct ALNS<'a> {
     final_temp: f32,
     alpha: Option<f32>,
     removal_params: RemovalParams,
+    /// Cap on how many distinct candidate hashes `visited` remembers (oldest
+    /// evicted first once full); `0` disables the cache.
+    cache_capacity: usize,
+    /// Cost already computed for a given candidate hash, so `candidate.cost`
+    /// isn't recomputed for an already evaluated solution while its hash is
+    /// still cached.
+    visited: HashMap<u64, Cost>,
+    /// FIFO eviction order for `visited`, bounded by `cache_capacity`.
+    visited_order: VecDeque<u64>,
+    /// Every evaluated candidate not dominated by another (see
+    /// `objectives::dominance_order`), alongside its objective vector.
+    pareto_archive: Vec<([Cost; N_OBJECTIVES], Solution)>,
+    /// Seeds the run's shared RNG (`run_inner`'s `thread_rng`), which drives
+    /// operator-pair selection, the removal operators and the acceptance
+    /// check. Insertion operators such as `random_placement_all` draw from
+    /// the thread-local RNG instead, so the same seed does not make a run
+    /// bit-for-bit reproducible.
+    seed: u64,
 }
 
 impl<'a> ALNS<'a> {
@@ -37,7 +118,9 @@ impl<'a> ALNS<'a> {
         rho: f32,
         segment_length: usize,
         score_params: ScoreParams,
-        final_temp: f32
+        final_temp: f32,
+        cache_capacity: usize,
+        seed: u64,
     ) -> Self {
         let n = operator_combinations.len();
         ALNS {
@@ -50,7 +133,101 @@ impl<'a> ALNS<'a> {
             score_params,
             final_temp,
             alpha: None,
-            removal_params: REMOVAL_PARAMS
+            removal_params: REMOVAL_PARAMS,
+            cache_capacity,
+            visited: HashMap::new(),
+            visited_order: VecDeque::new(),
+            pareto_archive: Vec::new(),
+            seed,
+        }
+    }
+
+    /// Candidate solutions evaluated so far that no other evaluated
+    /// candidate dominates, alongside the `objectives::evaluate` vector
+    /// each was archived under.
+    pub fn pareto_archive(&self) -> &[([Cost; N_OBJECTIVES], Solution)] {
+        &self.pareto_archive
+    }
+
+    /// Adds `candidate` to `pareto_archive` if nothing already there
+    /// dominates it, dropping any existing entry `candidate` in turn
+    /// dominates.
+    fn update_pareto_archive(&mut self, problem: &Problem, candidate: &Solution) {
+        let mut candidate = candidate.clone();
+        let objective_vector = objectives::evaluate(&mut candidate, problem);
+
+        let dominated = self
+            .pareto_archive
+            .iter()
+            .any(|(existing, _)| objectives::dominance_order(existing, &objective_vector) == std::cmp::Ordering::Less);
+        if dominated {
+            return;
+        }
+
+        self.pareto_archive
+            .retain(|(existing, _)| objectives::dominance_order(&objective_vector, existing) != std::cmp::Ordering::Less);
+        self.pareto_archive.push((objective_vector, candidate));
+
+        if self.pareto_archive.len() > PARETO_ARCHIVE_CAPACITY {
+            Self::evict_least_crowded(&mut self.pareto_archive);
+        }
+    }
+
+    /// Drops the archive entry with the smallest crowding distance
+    /// (NSGA-II style): for each objective, sort the archive by that
+    /// coordinate and give the two extremes infinite distance so the
+    /// archive's boundary is always kept, then give every interior entry the
+    /// sum, across objectives, of its normalized distance to its two
+    /// neighbors on that coordinate. The entry with the smallest total
+    /// distance sits in the densest neighborhood and is the least
+    /// informative one to keep.
+    fn evict_least_crowded(archive: &mut Vec<([Cost; N_OBJECTIVES], Solution)>) {
+        let n = archive.len();
+        let mut distance = vec![0.0_f64; n];
+
+        for obj in 0..N_OBJECTIVES {
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by_key(|&i| archive[i].0[obj]);
+
+            distance[order[0]] = f64::INFINITY;
+            distance[order[n - 1]] = f64::INFINITY;
+
+            let span = (archive[order[n - 1]].0[obj] - archive[order[0]].0[obj]) as f64;
+            if span <= 0.0 {
+                continue;
+            }
+
+            for w in 1..n - 1 {
+                let prev = archive[order[w - 1]].0[obj] as f64;
+                let next = archive[order[w + 1]].0[obj] as f64;
+                distance[order[w]] += (next - prev) / span;
+            }
+        }
+
+        if let Some((worst_idx, _)) = distance
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            archive.remove(worst_idx);
+        }
+    }
+
+    /// Records `cost` for `hash`, evicting the oldest entry once the cache
+    /// grows past `cache_capacity`. A no-op if the cache is disabled
+    /// (`cache_capacity == 0`).
+    fn remember_cost(&mut self, hash: u64, cost: Cost) {
+        if self.cache_capacity == 0 {
+            return;
+        }
+
+        if self.visited.insert(hash, cost).is_none() {
+            self.visited_order.push_back(hash);
+            if self.visited_order.len() > self.cache_capacity {
+                if let Some(oldest) = self.visited_order.pop_front() {
+                    self.visited.remove(&oldest);
+                }
+            }
         }
     }
 
@@ -59,7 +236,83 @@ impl<'a> ALNS<'a> {
         problem: &Problem,
         initial_solution: Solution,
         max_iter: usize,
-        mut iteration_data: Option<&mut Vec<IterationRecord>>,
+        deadline: Option<Instant>,
+        metrics: Option<&mut MetricsWriter>,
+    ) -> (Cost, Solution) {
+        self.run_inner(problem, initial_solution, max_iter, deadline, None, metrics)
+    }
+
+    /// Runs `islands.n_workers` independent ALNS workers over the same
+    /// `problem`, each with its own RNG (seeded off `seed`, offset per worker
+    /// so the workers don't draw identical sequences) and `weights`/`usage`/
+    /// `scores` state, periodically exchanging their best solution through a
+    /// shared leader board (see `Migration`). `n_workers <= 1` takes the
+    /// single-thread path and is equivalent to calling `run` directly.
+    /// Returns the best `(Cost, Solution)` found across every worker.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run_islands(
+        operator_combinations: &'a [OperatorPair],
+        rho: f32,
+        segment_length: usize,
+        score_params: ScoreParams,
+        final_temp: f32,
+        cache_capacity: usize,
+        problem: &Problem,
+        initial_solution: Solution,
+        max_iter: usize,
+        deadline: Option<Instant>,
+        islands: IslandParams,
+        seed: u64,
+    ) -> (Cost, Solution) {
+        if islands.n_workers <= 1 {
+            let mut alns = ALNS::new(operator_combinations, rho, segment_length, score_params, final_temp, cache_capacity, seed);
+            return alns.run(problem, initial_solution, max_iter, deadline, None);
+        }
+
+        let mut seed_solution = initial_solution.clone();
+        let seed_cost = seed_solution.cost(problem);
+        let board = Arc::new(Mutex::new((seed_cost, seed_solution)));
+
+        let results: Vec<(Cost, Solution)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..islands.n_workers)
+                .map(|worker| {
+                    let board = Arc::clone(&board);
+                    let start = initial_solution.clone();
+                    let worker_seed = seed ^ (worker as u64).wrapping_mul(0x9E3779B97F4A7C15);
+
+                    scope.spawn(move || {
+                        let mut alns = ALNS::new(operator_combinations, rho, segment_length, score_params, final_temp, cache_capacity, worker_seed);
+                        let migration = Migration {
+                            board,
+                            interval: islands.migration_interval.max(1),
+                            gap: islands.migration_gap,
+                        };
+                        alns.run_inner(problem, start, max_iter, deadline, Some(migration), None)
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("ALNS worker thread panicked"))
+                .collect()
+        });
+
+        results
+            .into_iter()
+            .min_by_key(|(cost, _)| *cost)
+            .expect("at least one worker must run")
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn run_inner(
+        &mut self,
+        problem: &Problem,
+        initial_solution: Solution,
+        max_iter: usize,
+        deadline: Option<Instant>,
+        migration: Option<Migration>,
+        mut metrics: Option<&mut MetricsWriter>,
     ) -> (Cost, Solution) {
         let mut incumbent = initial_solution;
         let mut best_solution = incumbent.clone();
@@ -75,21 +328,50 @@ impl<'a> ALNS<'a> {
         let mut progress = SearchProgress::new();
         progress.update_incumbent_cost(incumbent_cost);
 
-        let mut thread_rng = rand::rng();
+        let mut thread_rng = SmallRng::seed_from_u64(self.seed);
         let mut temp: f32 = 0.0;
 
+        // Rolling (exponentially weighted) average of per-iteration wall
+        // time, used when `deadline` is set to estimate how many more
+        // iterations fit in the remaining budget.
+        let mut avg_iter_time: f64 = 0.0;
+
         let mut segment_candidate_seen_total: usize = 0;
 
         for iteration in 0..max_iter {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+
             let start_time = Instant::now();
 
+            // Logged alongside this iteration's record so it can be traced
+            // back to its place in the run; doesn't itself reseed the
+            // destroy/repair/acceptance draws below, so it's a correlation
+            // id rather than a bit-for-bit replay key (see `IterationRecord::seed`).
+            let seed: u64 = thread_rng.random();
+
             let mut candidate = incumbent.clone();
 
             let dist = WeightedIndex::new(&self.weights).unwrap();
             let idx = dist.sample(&mut thread_rng);
             let (removal_op_fn, insertion_op_fn) = self.operator_combinations[idx];
 
-            let mut calls_to_remove = removal_op_fn(&candidate, &self.removal_params);
+            // `operator_combinations` pairs a destroy and repair operator
+            // together as one selectable unit, so both ids are recovered by
+            // identity from the canonical operator lists rather than tracked
+            // separately, and they share the pair's single adaptive weight.
+            let destroy_op = REMOVAL_OPERATORS
+                .iter()
+                .position(|&f| f as usize == removal_op_fn as usize)
+                .unwrap_or(usize::MAX);
+            let repair_op = INSERTION_OPERATORS
+                .iter()
+                .position(|&f| f as usize == insertion_op_fn as usize)
+                .unwrap_or(usize::MAX);
+            let pair_weight = self.weights[idx];
+
+            let mut calls_to_remove = removal_op_fn(&candidate, problem, &mut thread_rng, &self.removal_params);
 
             // If no calls were removed, we try to move unassigned calls
             if calls_to_remove.is_empty() {
@@ -107,12 +389,57 @@ impl<'a> ALNS<'a> {
                     .collect();
             }
 
+            let touched_calls = calls_to_remove.clone();
             let (evaluations, infeasible) = insertion_op_fn(&mut candidate, problem, calls_to_remove);
 
-            let candidate_cost = candidate.cost(problem);
+            // Polish every vehicle the destroy/repair step touched with a
+            // cheap exact-within-route permutation search before scoring the
+            // candidate, so small routes get an optimal reordering for free.
+            let touched_vehicles: HashSet<_> = touched_calls
+                .iter()
+                .filter_map(|&call| candidate.call_assignments()[call.index()])
+                .collect();
+            for vehicle in touched_vehicles {
+                local_search::permutation_polish(&mut candidate, problem, vehicle, local_search::PERMUTATION_POLISH_MAX_CALLS);
+            }
+
+            // If a touched call belongs to a precomputed vicinity cluster
+            // (see `Problem::call_clusters`) with other members still
+            // unassigned, try consolidating the whole cluster into one
+            // vehicle rather than leaving it to later single-call repairs.
+            let touched_cluster_indices: HashSet<usize> = touched_calls
+                .iter()
+                .filter_map(|&call| {
+                    problem
+                        .call_clusters()
+                        .iter()
+                        .position(|cluster| cluster.contains(&call.pickup()))
+                })
+                .collect();
+            for cluster_idx in touched_cluster_indices {
+                let cluster = &problem.call_clusters()[cluster_idx];
+                if cluster.len() > 1 {
+                    insertion::cluster_insertion(&mut candidate, problem, cluster);
+                }
+            }
+
+            let mut hasher = DefaultHasher::new();
+            candidate.hash(&mut hasher);
+            let candidate_hash = hasher.finish();
+
+            let cached = self.visited.get(&candidate_hash).copied();
+            let cache_hit = cached.is_some();
+            let candidate_cost = cached.unwrap_or_else(|| {
+                let cost = candidate.cost(problem);
+                self.remember_cost(candidate_hash, cost);
+                cost
+            });
             let delta_e = candidate_cost - incumbent_cost;
 
+            self.update_pareto_archive(problem, &candidate);
+
             progress.record_candidate(iteration, &candidate);
+            progress.record_cache_lookup(cache_hit);
 
             // Update usage counts
             let segment = iteration % self.segment_length;
@@ -126,6 +453,8 @@ impl<'a> ALNS<'a> {
                 self.scores[idx][segment] += self.score_params.novelty;
             }
 
+            let acceptance_outcome;
+
             if delta_e < 0 {
                 // Improvement
                 self.scores[idx][segment] += self.score_params.improvement;
@@ -140,6 +469,9 @@ impl<'a> ALNS<'a> {
                     best_cost = candidate_cost;
                     best_solution = candidate;
                     progress.update_best(iteration, best_solution.clone());
+                    acceptance_outcome = AcceptanceOutcome::NewBest;
+                } else {
+                    acceptance_outcome = AcceptanceOutcome::NewIncumbent;
                 }
             } else {
                 if delta_e > 0 {
@@ -147,19 +479,41 @@ impl<'a> ALNS<'a> {
                     delta_count += 1;
                 }
 
+                let accepted;
+
                 // Warm-up:
                 if self.alpha.is_none() {
                     // Accept with a fixed probability (e.g., 0.8)
-                    if thread_rng.random_bool(0.8) {
-                        incumbent = candidate.clone();
-                        incumbent_cost = candidate_cost;
-                    }
+                    accepted = thread_rng.random_bool(0.8);
                 } else {
                     // Otherwise, temperature based acceptance
                     let acceptance_probability = f32::exp(-delta_e as f32 / temp) as f64;
-                    if thread_rng.random_bool(acceptance_probability) {
-                        incumbent = candidate.clone();
-                        incumbent_cost = candidate_cost;
+                    accepted = thread_rng.random_bool(acceptance_probability);
+                }
+
+                if accepted {
+                    incumbent = candidate.clone();
+                    incumbent_cost = candidate_cost;
+                    acceptance_outcome = AcceptanceOutcome::Accepted;
+                } else {
+                    acceptance_outcome = AcceptanceOutcome::Rejected;
+                }
+            }
+
+            // Check in with the shared leader board (if running as part of
+            // `run_islands`), on its own `migration.interval` cadence rather
+            // than the segment boundary below: post our best if it improves
+            // on the global best, otherwise pull the global best into our
+            // incumbent if ours is worse by more than `migration.gap`.
+            if let Some(ref migration) = migration {
+                if (iteration + 1) % migration.interval == 0 {
+                    let mut board = migration.board.lock().unwrap();
+                    if best_cost < board.0 {
+                        board.0 = best_cost;
+                        board.1 = best_solution.clone();
+                    } else if best_cost > board.0 + migration.gap {
+                        incumbent = board.1.clone();
+                        incumbent_cost = incumbent.cost(problem);
                     }
                 }
             }
@@ -196,7 +550,7 @@ impl<'a> ALNS<'a> {
                     // Remove a percentage of calls from all vehicles
                     let removal_fraction: f32 = 0.5;
                     let num_remove = ((incumbent.call_assignments().len() as f32) * removal_fraction).ceil() as usize;
-                    let removal_list = random_calls(&incumbent, num_remove);
+                    let removal_list = random_calls(&incumbent, &mut thread_rng, num_remove);
 
                     for call in removal_list {
                         let _ = incumbent.remove_call(call);
@@ -213,7 +567,13 @@ impl<'a> ALNS<'a> {
                 if self.alpha.is_none() {
                     let delta_avg = if delta_count > 0 { delta_sum / delta_count as f32 } else { 1.0 };
                     let initial_temp = -delta_avg / f32::ln(0.8);
-                    let remaining_iter = max_iter - (iteration + 1);
+                    let remaining_iter = match deadline {
+                        Some(deadline) if avg_iter_time > 0.0 => {
+                            let time_left = deadline.saturating_duration_since(Instant::now()).as_secs_f64();
+                            ((time_left / avg_iter_time).round() as usize).max(1)
+                        }
+                        _ => max_iter - (iteration + 1),
+                    };
                     let computed_alpha = (self.final_temp / initial_temp).powf(1.0 / (remaining_iter as f32));
                     self.alpha = Some(computed_alpha);
                     temp = initial_temp;
@@ -225,8 +585,16 @@ impl<'a> ALNS<'a> {
                 temp *= alpha;
             }
 
-            if let Some(ref mut iter_data) = iteration_data {
-                iter_data.push(IterationRecord {
+            let iteration_time = start_time.elapsed().as_secs_f64();
+            avg_iter_time = if avg_iter_time == 0.0 {
+                iteration_time
+            } else {
+                0.9 * avg_iter_time + 0.1 * iteration_time
+            };
+
+            if let Some(ref mut writer) = metrics {
+                writer.push(IterationRecord {
+                    island: 0,
                     iteration,
                     candidate_cost,
                     candidate_seen: progress.candidate_seen(),
@@ -234,8 +602,17 @@ impl<'a> ALNS<'a> {
                     best_cost,
                     evaluations,
                     infeasible,
-                    time: start_time.elapsed().as_secs_f64(),
+                    time: iteration_time,
                     temperature: if self.alpha.is_some() { Some(temp) } else { None },
+                    operator_weights: self.weights.clone(),
+                    reward: 0.0,
+                    cache_hit,
+                    destroy_op,
+                    repair_op,
+                    destroy_weight: pair_weight,
+                    repair_weight: Some(pair_weight),
+                    outcome: acceptance_outcome,
+                    seed,
                 });
             }
         }
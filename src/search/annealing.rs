@@ -1,23 +1,265 @@
 use rand::Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Instant;
 
-use crate::operators::mutate::roulette_wheel_tuned;
+use crate::operators::mutate::{ReactiveRemovalWeights, PSI_ACCEPTED_WORSE, PSI_IMPROVED_INCUMBENT, PSI_NEW_BEST};
 use crate::problem::Problem;
 use crate::solution::Solution;
 use crate::types::Cost;
 
-use crate::metrics::IterationRecord;
-use crate::search::progress::SearchProgress;
+use crate::metrics::{AcceptanceOutcome, IterationRecord};
+use crate::search::acceptance::AcceptanceStrategy;
+use crate::search::progress::{ProgressHook, SearchControl, SearchProgress, SearchStatus, StopConditions};
 
+/// How islands are wired together for migration. `FullyConnected` (the
+/// long-standing behavior) has every island post to and read from one
+/// shared leader board, so the fastest-improving island's best propagates
+/// to all the others in a single migration step. `Ring` instead gives each
+/// island its own board to post to and only reads its ring predecessor's
+/// board, so a migrant takes `n_islands - 1` steps to reach every island,
+/// trading migration speed for preserving more distinct search basins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IslandTopology {
+    FullyConnected,
+    Ring,
+}
+
+impl Default for IslandTopology {
+    fn default() -> Self {
+        IslandTopology::FullyConnected
+    }
+}
+
+/// Parameters for the parallel multi-start driver: how many independent
+/// annealing chains ("islands") to run side by side, how they're wired
+/// together (`topology`), and how often/likely they migrate their incumbent
+/// toward the board(s) they read from.
+#[derive(Clone, Copy, Debug)]
+pub struct IslandParams {
+    /// Number of independent annealing chains. `1` takes the single-thread path.
+    pub n_islands: usize,
+    /// Iterations between an island's check-ins with its leader board(s).
+    pub migration_interval: usize,
+    /// Probability an island adopts the migrated solution as its incumbent.
+    pub migration_probability: f64,
+    /// How islands are wired together for migration.
+    pub topology: IslandTopology,
+}
+
+impl Default for IslandParams {
+    fn default() -> Self {
+        IslandParams {
+            n_islands: 1,
+            migration_interval: 200,
+            migration_probability: 0.3,
+            topology: IslandTopology::FullyConnected,
+        }
+    }
+}
+
+/// Shared leader board islands post their best solution to, and read the
+/// global best from, every `migration_interval` iterations. Solutions are
+/// deduplicated by `Solution`'s assignment-based hash so that migration can't
+/// collapse every island onto the same basin.
+struct LeaderBoard {
+    best_cost: Cost,
+    best_solution: Solution,
+    best_hash: u64,
+    seen_hashes: HashSet<u64>,
+}
+
+impl LeaderBoard {
+    fn new(cost: Cost, solution: Solution) -> Self {
+        let hash = solution_hash(&solution);
+        let mut seen_hashes = HashSet::new();
+        seen_hashes.insert(hash);
+
+        LeaderBoard {
+            best_cost: cost,
+            best_solution: solution,
+            best_hash: hash,
+            seen_hashes,
+        }
+    }
+
+    /// Considers `(cost, solution)` for this board's best, keeping it if it
+    /// improves on and is distinct from what's already been seen. Posting
+    /// and reading back are separate steps (see `best`) so `Ring` topology
+    /// can post to one board and read from another.
+    fn update(&mut self, cost: Cost, solution: &Solution) {
+        let hash = solution_hash(solution);
+
+        if cost < self.best_cost && self.seen_hashes.insert(hash) {
+            self.best_cost = cost;
+            self.best_solution = solution.clone();
+            self.best_hash = hash;
+        }
+    }
+
+    /// This board's current best and its hash.
+    fn best(&self) -> (Solution, u64) {
+        (self.best_solution.clone(), self.best_hash)
+    }
+}
+
+fn solution_hash(solution: &Solution) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    solution.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Runs `simulated_annealing` on a single thread (`islands.n_islands == 1`), or
+/// as `islands.n_islands` independent annealing chains that periodically
+/// migrate their incumbent through leader board(s) (as ED_LRR pumps
+/// `SearchState` between islands over a channel), wired together per
+/// `islands.topology`. Every `islands.migration_interval` iterations, each
+/// island posts its best cost/solution to the board(s) it's wired to and,
+/// with `islands.migration_probability`, replaces its incumbent with
+/// whatever it reads back to re-intensify. Per-island
+/// `IterationRecord`s are tagged with their `island` id and merged in
+/// iteration order. `strategy` selects the acceptance criterion (Metropolis,
+/// LAHC, ...); each island builds its own instance so their internal state
+/// (temperature, history, ...) never crosses threads. `stop` conditions are
+/// checked every main-loop iteration and `hook`'s callback every
+/// `hook.interval` iterations, across every island; any island's callback
+/// returning `SearchControl::Abort` stops the
+/// whole run.
 pub fn simulated_annealing(
     problem: &Problem,
-    mut incumbent: Solution,
+    incumbent: Solution,
     max_iter: usize,
     warmup_iter: usize,
     final_temp: f32,
-    mut iteration_data: Option<&mut Vec<IterationRecord>>,
+    strategy: AcceptanceStrategy,
+    islands: IslandParams,
+    stop: StopConditions,
+    hook: Option<ProgressHook>,
+    iteration_data: Option<&mut Vec<IterationRecord>>,
 ) -> (Cost, Solution) {
+    let abort = Arc::new(AtomicBool::new(false));
+
+    if islands.n_islands <= 1 {
+        let (best_cost, best_solution, records) = run_island(
+            problem, incumbent, max_iter, warmup_iter, final_temp, strategy, stop, hook, abort, 0, None,
+        );
+
+        if let Some(data) = iteration_data {
+            data.extend(records);
+        }
+
+        return (best_cost, best_solution);
+    }
+
+    // `FullyConnected` gives every island the same single board; `Ring` gives
+    // each island its own, so posts only reach the next island around the ring.
+    let n_boards = match islands.topology {
+        IslandTopology::FullyConnected => 1,
+        IslandTopology::Ring => islands.n_islands,
+    };
+    let boards: Vec<Arc<Mutex<LeaderBoard>>> = (0..n_boards)
+        .map(|_| {
+            let seed_solution = incumbent.clone();
+            let seed_cost = seed_solution.cost(problem);
+            Arc::new(Mutex::new(LeaderBoard::new(seed_cost, seed_solution)))
+        })
+        .collect();
+
+    let results: Vec<(Cost, Solution, Vec<IterationRecord>)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..islands.n_islands)
+            .map(|island| {
+                let (post_board, read_board) = match islands.topology {
+                    IslandTopology::FullyConnected => (Arc::clone(&boards[0]), Arc::clone(&boards[0])),
+                    IslandTopology::Ring => {
+                        let predecessor = (island + islands.n_islands - 1) % islands.n_islands;
+                        (Arc::clone(&boards[island]), Arc::clone(&boards[predecessor]))
+                    }
+                };
+                let abort = Arc::clone(&abort);
+                let hook = hook.clone();
+                let start = incumbent.clone();
+
+                scope.spawn(move || {
+                    let migration = Migration {
+                        post_board,
+                        read_board,
+                        interval: islands.migration_interval,
+                        probability: islands.migration_probability,
+                    };
+
+                    run_island(
+                        problem, start, max_iter, warmup_iter, final_temp, strategy, stop, hook, abort, island,
+                        Some(migration),
+                    )
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("island thread panicked"))
+            .collect()
+    });
+
+    let mut best_cost = Cost::MAX;
+    let mut best_solution = None;
+    let mut all_records = Vec::new();
+
+    for (cost, solution, records) in results {
+        all_records.extend(records);
+
+        if best_solution.is_none() || cost < best_cost {
+            best_cost = cost;
+            best_solution = Some(solution);
+        }
+    }
+
+    if let Some(data) = iteration_data {
+        all_records.sort_by_key(|record| record.iteration);
+        data.extend(all_records);
+    }
+
+    (best_cost, best_solution.expect("at least one island must run"))
+}
+
+/// Handle an island uses to check in with its leader board(s): `post_board`
+/// is where it publishes its own best, `read_board` is where it reads a
+/// migration candidate from. Under `IslandTopology::FullyConnected` these
+/// are the same board; under `Ring` `read_board` is the island's predecessor.
+struct Migration {
+    post_board: Arc<Mutex<LeaderBoard>>,
+    read_board: Arc<Mutex<LeaderBoard>>,
+    interval: usize,
+    probability: f64,
+}
+
+/// Runs one annealing chain tagged with `island`, optionally checking in with
+/// `migration` every `migration.interval` iterations. `stop` is evaluated
+/// every main-loop iteration and `hook` every `hook.interval` iterations;
+/// hitting a stop condition or an
+/// `Abort` from the hook's callback sets `abort` (so sibling islands stop
+/// too) and ends this chain's loop early.
+#[allow(clippy::too_many_arguments)]
+fn run_island(
+    problem: &Problem,
+    mut incumbent: Solution,
+    max_iter: usize,
+    warmup_iter: usize,
+    final_temp: f32,
+    strategy: AcceptanceStrategy,
+    stop: StopConditions,
+    hook: Option<ProgressHook>,
+    abort: Arc<AtomicBool>,
+    island: usize,
+    migration: Option<Migration>,
+) -> (Cost, Solution, Vec<IterationRecord>) {
+    let run_start = Instant::now();
     let mut thread_rng = rand::rng();
+    let mut iteration_data = Vec::new();
 
     let mut best_cost = incumbent.cost(problem);
     let mut best_solution = incumbent.clone();
@@ -25,31 +267,44 @@ pub fn simulated_annealing(
 
     let mut delta_sum = 0.0;
     let mut delta_count = 0;
-    
+
     // Initialize progress tracker
     let mut progress = SearchProgress::new();
     progress.update_incumbent_cost(incumbent_cost);
 
+    // Reactive weights over the removal operators used by `roulette_wheel_tuned`,
+    // seeded from its tuned weights and reinforced by how well each choice pays off.
+    let mut operator_weights = ReactiveRemovalWeights::new();
+
     // Warm-up
     for i in 0..warmup_iter {
         let start_time = Instant::now();
-    
+        let seed: u64 = thread_rng.random();
+
         let mut candidate = incumbent.clone();
-        
-        let (evaluations, infeasible) = roulette_wheel_tuned(&mut candidate, problem);
+
+        let outcome = operator_weights.choose_and_apply(&mut candidate, problem, &mut thread_rng);
+        let (evaluations, infeasible) = (outcome.evaluations, outcome.infeasible);
 
         let candidate_cost = candidate.cost(problem);
         let delta_e = candidate_cost - incumbent_cost;
 
         progress.record_candidate(i, &candidate);
 
+        let mut reward = 0.0;
+        let acceptance_outcome;
+
         if delta_e < 0 {
             incumbent = candidate;
             incumbent_cost = candidate_cost;
+            reward = PSI_IMPROVED_INCUMBENT;
+            acceptance_outcome = AcceptanceOutcome::NewIncumbent;
+
             if incumbent_cost < best_cost {
                 best_cost = incumbent_cost;
                 best_solution = incumbent.clone();
                 progress.update_best(i, best_solution.clone());
+                reward = PSI_NEW_BEST;
             }
         } else {
             if delta_e > 0 {
@@ -60,22 +315,38 @@ pub fn simulated_annealing(
             if thread_rng.random_bool(0.8) {
                 incumbent = candidate;
                 incumbent_cost = candidate_cost;
+                reward = PSI_ACCEPTED_WORSE;
+                acceptance_outcome = AcceptanceOutcome::Accepted;
+            } else {
+                acceptance_outcome = AcceptanceOutcome::Rejected;
             }
         }
 
-        if let Some(ref mut iter_data) = iteration_data {
-            iter_data.push(IterationRecord {
-                iteration: i,
-                candidate_cost,
-                candidate_seen: progress.candidate_seen(),
-                incumbent_cost,
-                best_cost,
-                evaluations,
-                infeasible,
-                time: start_time.elapsed().as_secs_f64(),
-                temperature: None
-            });
-        }
+        let acceptance_outcome = if reward == PSI_NEW_BEST { AcceptanceOutcome::NewBest } else { acceptance_outcome };
+
+        operator_weights.reward(outcome.operator_idx, reward);
+
+        iteration_data.push(IterationRecord {
+            island,
+            iteration: i,
+            candidate_cost,
+            candidate_seen: progress.candidate_seen(),
+            incumbent_cost,
+            best_cost,
+            evaluations,
+            infeasible,
+            time: start_time.elapsed().as_secs_f64(),
+            temperature: None,
+            operator_weights: operator_weights.weights().to_vec(),
+            reward,
+            cache_hit: false,
+            destroy_op: outcome.operator_idx,
+            repair_op: 0,
+            destroy_weight: operator_weights.weights()[outcome.operator_idx],
+            repair_weight: None,
+            outcome: acceptance_outcome,
+            seed,
+        });
     }
 
     let delta_avg = if delta_count > 0 {
@@ -84,51 +355,123 @@ pub fn simulated_annealing(
         1.0
     };
 
-    // Initial temperature and cooling factor.
-    let mut temp = -delta_avg / f32::ln(0.8);
-    let alpha = (final_temp / temp).powf(1.0 / (max_iter.saturating_sub(warmup_iter) as f32));
+    // Calibrate the acceptance criterion's cooling schedule (if it has one)
+    // from the warm-up phase's observed average worsening move.
+    let mut acceptance = strategy.build(incumbent_cost);
+    acceptance.calibrate(delta_avg, final_temp, max_iter.saturating_sub(warmup_iter));
 
     // Main annealing loop.
     for i in warmup_iter..max_iter {
         let start_time = Instant::now();
+        let seed: u64 = thread_rng.random();
 
         let mut candidate = incumbent.clone();
-        
-        let (evaluations, infeasible) = roulette_wheel_tuned(&mut candidate, problem);
+
+        let outcome = operator_weights.choose_and_apply(&mut candidate, problem, &mut thread_rng);
+        let (evaluations, infeasible) = (outcome.evaluations, outcome.infeasible);
 
         let candidate_cost = candidate.cost(problem);
         let delta_e = candidate_cost - incumbent_cost;
 
         progress.record_candidate(i, &candidate);
 
-        if delta_e < 0 {
+        let mut reward = 0.0;
+        let accepted = acceptance.accept(delta_e, incumbent_cost, best_cost, &mut thread_rng);
+        progress.record_acceptance(accepted);
+        let mut acceptance_outcome = if accepted { AcceptanceOutcome::Accepted } else { AcceptanceOutcome::Rejected };
+
+        if accepted {
             incumbent = candidate;
             incumbent_cost = candidate_cost;
+            reward = if delta_e < 0 { PSI_IMPROVED_INCUMBENT } else { PSI_ACCEPTED_WORSE };
+            if delta_e < 0 {
+                acceptance_outcome = AcceptanceOutcome::NewIncumbent;
+            }
+
             if incumbent_cost < best_cost {
                 best_cost = incumbent_cost;
                 best_solution = incumbent.clone();
                 progress.update_best(i, best_solution.clone());
+                reward = PSI_NEW_BEST;
+                acceptance_outcome = AcceptanceOutcome::NewBest;
             }
-        } else if thread_rng.random_bool(f32::exp(-delta_e as f32 / temp) as f64) {
-            incumbent = candidate;
-            incumbent_cost = candidate_cost;
         }
-        temp *= alpha;
-
-        if let Some(ref mut iter_data) = iteration_data {
-            iter_data.push(IterationRecord {
-                iteration: i,
-                candidate_cost,
-                candidate_seen: progress.candidate_seen(),
-                incumbent_cost,
-                best_cost,
-                evaluations,
-                infeasible,
-                time: start_time.elapsed().as_secs_f64(),
-                temperature: Some(temp)
-            });
+
+        operator_weights.reward(outcome.operator_idx, reward);
+
+        if let Some(hook) = &hook {
+            if i % hook.interval == 0 {
+                let status = SearchStatus {
+                    island,
+                    iteration: i,
+                    elapsed: run_start.elapsed(),
+                    incumbent_cost,
+                    best_cost,
+                    acceptance_rate: progress.acceptance_rate(),
+                    temperature: acceptance.temperature(),
+                    operator_weights: operator_weights.weights().to_vec(),
+                };
+
+                let control = (hook.callback.lock().unwrap())(&status);
+
+                if control == SearchControl::Abort {
+                    abort.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        if let Some(migration) = &migration {
+            if i > 0 && i % migration.interval == 0 {
+                let caller_hash = solution_hash(&incumbent);
+
+                {
+                    let mut post_board = migration.post_board.lock().unwrap();
+                    post_board.update(best_cost, &best_solution);
+                }
+
+                let (migrated_solution, migrated_hash) = {
+                    let read_board = migration.read_board.lock().unwrap();
+                    read_board.best()
+                };
+
+                if migrated_hash != caller_hash && thread_rng.random_bool(migration.probability) {
+                    incumbent_cost = migrated_solution.clone().cost(problem);
+                    incumbent = migrated_solution;
+                }
+            }
+        }
+
+        iteration_data.push(IterationRecord {
+            island,
+            iteration: i,
+            candidate_cost,
+            candidate_seen: progress.candidate_seen(),
+            incumbent_cost,
+            best_cost,
+            evaluations,
+            infeasible,
+            time: start_time.elapsed().as_secs_f64(),
+            temperature: acceptance.temperature(),
+            operator_weights: operator_weights.weights().to_vec(),
+            reward,
+            cache_hit: false,
+            destroy_op: outcome.operator_idx,
+            repair_op: 0,
+            destroy_weight: operator_weights.weights()[outcome.operator_idx],
+            repair_weight: None,
+            outcome: acceptance_outcome,
+            seed,
+        });
+
+        if abort.load(Ordering::Relaxed)
+            || stop.wall_clock_budget.is_some_and(|budget| run_start.elapsed() >= budget)
+            || stop.stall_limit.is_some_and(|limit| progress.stall(i) >= limit)
+            || stop.target_cost.is_some_and(|target| best_cost <= target)
+        {
+            abort.store(true, Ordering::Relaxed);
+            break;
         }
     }
 
-    (best_cost, best_solution)
+    (best_cost, best_solution, iteration_data)
 }
@@ -14,9 +14,11 @@ pub fn local_search(
 
     let mut _infeasible_count = 0;
 
+    let mut thread_rng = rand::rng();
+
     for _ in 0..max_iter {
         current_solution = best_solution.clone();
-        mutate(&mut current_solution, problem);
+        mutate(&mut current_solution, problem, &mut thread_rng);
 
         if current_solution.feasible(problem).is_err() {
             _infeasible_count += 1;
@@ -1,25 +1,116 @@
 use crate::problem::Problem;
 use crate::solution::Solution;
+use crate::search::annealing::{IslandParams, IslandTopology};
 use crate::search::progress::SearchProgress;
 use crate::types::{CallId, Cost, OperatorPair};
+use crate::operators::local_search;
 use crate::operators::params::RemovalParams;
 
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rand::distr::weighted::WeightedIndex;
 use rand::prelude::*;
 
+/// Reward amounts awarded to the chosen operator pair's running score each
+/// iteration (see `Pooled::update_weights`): `sigma1` for a new global best,
+/// `sigma2` for an improved incumbent that wasn't a new best, `sigma3` for
+/// an accepted worsening move, and 0 otherwise. `r` is the reaction factor
+/// blending a segment's average score into the operator's weight, and
+/// `segment_length` is how many iterations make up a segment.
+#[derive(Copy, Clone)]
+pub struct AdaptiveWeightParams {
+    pub sigma1: f32,
+    pub sigma2: f32,
+    pub sigma3: f32,
+    pub r: f32,
+    pub segment_length: usize,
+}
+
+/// Bounds for `Pooled`'s visited-solution cache: `capacity` caps how many
+/// distinct route hashes are remembered (oldest evicted first once full;
+/// `0` disables the cache), and `tabu_window` caps how many of the most
+/// recently *accepted* hashes are refused outright regardless of `delta_e`,
+/// to discourage cycling between two states (`0` disables the tabu list).
+#[derive(Copy, Clone)]
+pub struct CacheParams {
+    pub capacity: usize,
+    pub tabu_window: usize,
+}
+
 pub struct Pooled<'a> {
     operator_combinations: &'a [OperatorPair],
     removal_params: RemovalParams,
+    adaptive_params: AdaptiveWeightParams,
+    cache_params: CacheParams,
+    /// Roulette-wheel weight per operator pair, sampled via `WeightedIndex`.
+    weights: Vec<f32>,
+    /// Accumulated score per operator pair within the current segment.
+    scores: Vec<f32>,
+    /// Number of times each operator pair was chosen within the current segment.
+    uses: Vec<u32>,
+    /// Cost already computed for a given route hash, so `candidate.cost`
+    /// isn't recomputed for an already evaluated route while its hash is
+    /// still cached.
+    visited: HashMap<u64, Cost>,
+    /// FIFO eviction order for `visited`, bounded by `cache_params.capacity`.
+    visited_order: VecDeque<u64>,
+    /// Most recently accepted route hashes, bounded by `cache_params.tabu_window`.
+    tabu: VecDeque<u64>,
 }
 
 impl<'a> Pooled<'a> {
     pub fn new(
         operator_combinations: &'a [OperatorPair],
-        removal_params: RemovalParams
+        removal_params: RemovalParams,
+        adaptive_params: AdaptiveWeightParams,
+        cache_params: CacheParams,
     ) -> Self {
         let n = operator_combinations.len();
         Pooled {
             operator_combinations,
             removal_params,
+            adaptive_params,
+            cache_params,
+            weights: vec![1.0; n],
+            scores: vec![0.0; n],
+            uses: vec![0; n],
+            visited: HashMap::new(),
+            visited_order: VecDeque::new(),
+            tabu: VecDeque::new(),
+        }
+    }
+
+    /// Records `cost` for `hash`, evicting the oldest entry once the cache
+    /// grows past `cache_params.capacity`. A no-op if the cache is disabled
+    /// (`capacity == 0`).
+    fn remember_cost(&mut self, hash: u64, cost: Cost) {
+        if self.cache_params.capacity == 0 {
+            return;
+        }
+
+        if self.visited.insert(hash, cost).is_none() {
+            self.visited_order.push_back(hash);
+            if self.visited_order.len() > self.cache_params.capacity {
+                if let Some(oldest) = self.visited_order.pop_front() {
+                    self.visited.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Adds `hash` to the tabu window, dropping the oldest entry once it
+    /// grows past `cache_params.tabu_window`. A no-op if the tabu list is
+    /// disabled (`tabu_window == 0`).
+    fn mark_accepted(&mut self, hash: u64) {
+        if self.cache_params.tabu_window == 0 {
+            return;
+        }
+
+        self.tabu.push_back(hash);
+        if self.tabu.len() > self.cache_params.tabu_window {
+            self.tabu.pop_front();
         }
     }
 
@@ -30,7 +121,7 @@ impl<'a> Pooled<'a> {
         max_iter: usize,
         mut temp: f32,
         alpha: f32,
-    ) -> (Cost, Solution) {
+    ) -> (Cost, Solution, SearchProgress) {
         let mut incumbent = initial_solution;
         let mut best_solution = incumbent.clone();
         let mut best_cost = incumbent.cost(problem);
@@ -44,10 +135,12 @@ impl<'a> Pooled<'a> {
         for iteration in 0..max_iter {
             let mut candidate = incumbent.clone();
 
-            let idx = thread_rng.random_range(0..self.operator_combinations.len());
+            let dist = WeightedIndex::new(&self.weights).unwrap();
+            let idx = dist.sample(&mut thread_rng);
+            self.uses[idx] += 1;
             let (removal_op_fn, insertion_op_fn) = self.operator_combinations[idx];
 
-            let mut calls_to_remove = removal_op_fn(&candidate, &self.removal_params);
+            let mut calls_to_remove = removal_op_fn(&candidate, problem, &mut thread_rng, &self.removal_params);
 
             // If no calls were removed, we try to move unassigned calls
             if calls_to_remove.is_empty() {
@@ -65,21 +158,50 @@ impl<'a> Pooled<'a> {
                     .collect();
             }
 
+            let touched_calls = calls_to_remove.clone();
             let (evaluations, infeasible) = insertion_op_fn(&mut candidate, problem, calls_to_remove);
 
-            let candidate_cost = candidate.cost(problem);
+            let touched_vehicles: HashSet<_> = touched_calls
+                .iter()
+                .filter_map(|&call| candidate.call_assignments()[call.index()])
+                .collect();
+            for vehicle in touched_vehicles {
+                local_search::permutation_polish(&mut candidate, problem, vehicle, local_search::PERMUTATION_POLISH_MAX_CALLS);
+            }
+
+            let candidate_hash = candidate.route_hash(problem);
+            let tabu_hit = self.cache_params.tabu_window > 0 && self.tabu.contains(&candidate_hash);
+
+            let candidate_cost = if let Some(&cached) = self.visited.get(&candidate_hash) {
+                progress.record_cache_lookup(true);
+                cached
+            } else {
+                progress.record_cache_lookup(false);
+                let cost = candidate.cost(problem);
+                self.remember_cost(candidate_hash, cost);
+                cost
+            };
+
             let delta_e = candidate_cost - incumbent_cost;
-            
-            if delta_e < 0 {
+
+            if tabu_hit {
+                // A tabu hit is rejected outright, regardless of delta_e, to
+                // discourage cycling straight back to a state we just left.
+            } else if delta_e < 0 {
                 // Improvement
                 incumbent = candidate.clone();
                 incumbent_cost = candidate_cost;
+                self.mark_accepted(candidate_hash);
 
                 if candidate_cost < best_cost {
                     // New best solution found
+                    self.scores[idx] += self.adaptive_params.sigma1;
+
                     best_cost = candidate_cost;
                     best_solution = candidate;
                     progress.update_best(iteration, best_solution.clone());
+                } else {
+                    self.scores[idx] += self.adaptive_params.sigma2;
                 }
             } else {
                 // Worsening:
@@ -88,13 +210,254 @@ impl<'a> Pooled<'a> {
                 if thread_rng.random_bool(acceptance_probability) {
                     incumbent = candidate.clone();
                     incumbent_cost = candidate_cost;
+                    self.mark_accepted(candidate_hash);
+                    self.scores[idx] += self.adaptive_params.sigma3;
                 }
             }
 
             // Update temperature (cooling schedule)
             temp *= alpha;
+
+            if (iteration + 1) % self.adaptive_params.segment_length == 0 {
+                self.update_weights();
+            }
+        }
+
+        (best_cost, best_solution, progress)
+    }
+
+    /// Blends each operator pair's average score this segment into its
+    /// roulette weight with reaction factor `r`, then resets the segment's
+    /// scores and uses. A pair unused this segment has its average score
+    /// taken as 0, so its weight decays by `1 - r` (never below 0.1).
+    fn update_weights(&mut self) {
+        let r = self.adaptive_params.r;
+
+        for i in 0..self.weights.len() {
+            let average_score = self.scores[i] / self.uses[i].max(1) as f32;
+            self.weights[i] = (self.weights[i] * (1.0 - r) + r * average_score).max(0.1);
+        }
+
+        self.scores.fill(0.0);
+        self.uses.fill(0);
+    }
+}
+
+/// One island's starting point for `run_islands`: its own initial solution
+/// (e.g. a distinct draw from `weighted_random_calls`) and its own cooling
+/// schedule, so islands can be seeded and annealed differently.
+#[derive(Clone)]
+pub struct IslandStart {
+    pub solution: Solution,
+    pub t0: f32,
+    pub alpha: f32,
+}
+
+/// Shared leader board islands post their best solution to, and read the
+/// global best from, every `migration_interval` iterations. Solutions are
+/// deduplicated by `Solution::route_hash` so that migration can't collapse
+/// every island onto the same basin (mirroring `annealing::LeaderBoard`,
+/// which dedups on `Solution`'s coarser assignment-based hash instead).
+struct LeaderBoard {
+    best_cost: Cost,
+    best_solution: Solution,
+    best_hash: u64,
+    seen_hashes: HashSet<u64>,
+}
+
+impl LeaderBoard {
+    fn new(cost: Cost, solution: Solution, hash: u64) -> Self {
+        let mut seen_hashes = HashSet::new();
+        seen_hashes.insert(hash);
+
+        LeaderBoard {
+            best_cost: cost,
+            best_solution: solution,
+            best_hash: hash,
+            seen_hashes,
+        }
+    }
+
+    /// Considers an island's `(cost, solution, hash)` for this board's best,
+    /// keeping it if it improves on and is distinct from what's already been
+    /// seen. Posting and reading back are separate steps (see `best`) so
+    /// `IslandTopology::Ring` can post to one board and read from another.
+    fn update(&mut self, cost: Cost, solution: &Solution, hash: u64) {
+        if cost < self.best_cost && self.seen_hashes.insert(hash) {
+            self.best_cost = cost;
+            self.best_solution = solution.clone();
+            self.best_hash = hash;
+        }
+    }
+
+    /// This board's current best and its hash.
+    fn best(&self) -> (Solution, u64) {
+        (self.best_solution.clone(), self.best_hash)
+    }
+}
+
+/// Runs `starts.len()` independent `Pooled` searches side by side, one per
+/// `starts` entry, each for `max_iter` iterations total. With more than one
+/// island, every `island_params.migration_interval` iterations each island
+/// posts its best solution to the leader board(s) it's wired to, per
+/// `island_params.topology`, and with `island_params.migration_probability`
+/// adopts whatever it reads back as its next segment's starting incumbent to
+/// re-intensify (the same island model as `annealing::simulated_annealing`,
+/// adapted to `Pooled`'s segment-at-a-time `run`). Returns the global best
+/// across all islands, together with every island's `SearchProgress` for the
+/// caller to report or merge.
+pub fn run_islands(
+    problem: &Problem,
+    starts: Vec<IslandStart>,
+    operator_combinations: &[OperatorPair],
+    removal_params: RemovalParams,
+    adaptive_params: AdaptiveWeightParams,
+    cache_params: CacheParams,
+    island_params: IslandParams,
+    max_iter: usize,
+) -> (Cost, Solution, Vec<SearchProgress>) {
+    assert!(!starts.is_empty(), "at least one island must run");
+
+    if island_params.n_islands <= 1 || starts.len() <= 1 {
+        let start = starts.into_iter().next().unwrap();
+        let mut search = Pooled::new(operator_combinations, removal_params, adaptive_params, cache_params);
+        let (cost, solution, progress) = search.run(problem, start.solution, max_iter, start.t0, start.alpha);
+        return (cost, solution, vec![progress]);
+    }
+
+    let seed = &starts[0];
+    let mut seed_solution = seed.solution.clone();
+    let seed_cost = seed_solution.cost(problem);
+    let seed_hash = seed_solution.route_hash(problem);
+
+    // `FullyConnected` gives every island the same single board; `Ring` gives
+    // each island its own, so posts only reach the next island around the ring.
+    let n_boards = match island_params.topology {
+        IslandTopology::FullyConnected => 1,
+        IslandTopology::Ring => island_params.n_islands,
+    };
+    let boards: Vec<Arc<Mutex<LeaderBoard>>> = (0..n_boards)
+        .map(|_| Arc::new(Mutex::new(LeaderBoard::new(seed_cost, seed_solution.clone(), seed_hash))))
+        .collect();
+
+    let results: Vec<(Cost, Solution, SearchProgress)> = thread::scope(|scope| {
+        let handles: Vec<_> = starts
+            .into_iter()
+            .enumerate()
+            .map(|(island, start)| {
+                let (post_board, read_board) = match island_params.topology {
+                    IslandTopology::FullyConnected => (Arc::clone(&boards[0]), Arc::clone(&boards[0])),
+                    IslandTopology::Ring => {
+                        let predecessor = (island + island_params.n_islands - 1) % island_params.n_islands;
+                        (Arc::clone(&boards[island]), Arc::clone(&boards[predecessor]))
+                    }
+                };
+
+                scope.spawn(move || {
+                    run_island(
+                        problem,
+                        start,
+                        operator_combinations,
+                        removal_params,
+                        adaptive_params,
+                        cache_params,
+                        max_iter,
+                        island_params.migration_interval,
+                        island_params.migration_probability,
+                        post_board,
+                        read_board,
+                    )
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("island thread panicked"))
+            .collect()
+    });
+
+    let mut best_cost = Cost::MAX;
+    let mut best_solution = None;
+    let mut progresses = Vec::with_capacity(results.len());
+
+    for (cost, solution, progress) in results {
+        progresses.push(progress);
+
+        if best_solution.is_none() || cost < best_cost {
+            best_cost = cost;
+            best_solution = Some(solution);
+        }
+    }
+
+    (best_cost, best_solution.expect("at least one island must run"), progresses)
+}
+
+/// Runs one island's `Pooled` search in `migration_interval`-sized segments
+/// (a single segment of `max_iter` iterations if `migration_interval == 0`),
+/// checking in between segments: posts its best to `post_board` and, with
+/// probability `migration_probability`, adopts whatever it reads back from
+/// `read_board` as the next segment's starting incumbent. Under
+/// `IslandTopology::FullyConnected` these are the same board; under `Ring`
+/// `read_board` is the island's predecessor.
+#[allow(clippy::too_many_arguments)]
+fn run_island(
+    problem: &Problem,
+    start: IslandStart,
+    operator_combinations: &[OperatorPair],
+    removal_params: RemovalParams,
+    adaptive_params: AdaptiveWeightParams,
+    cache_params: CacheParams,
+    max_iter: usize,
+    migration_interval: usize,
+    migration_probability: f64,
+    post_board: Arc<Mutex<LeaderBoard>>,
+    read_board: Arc<Mutex<LeaderBoard>>,
+) -> (Cost, Solution, SearchProgress) {
+    let mut search = Pooled::new(operator_combinations, removal_params, adaptive_params, cache_params);
+    let mut thread_rng = rand::rng();
+
+    let segment_len = if migration_interval == 0 { max_iter } else { migration_interval };
+
+    let mut best_cost = Cost::MAX;
+    let mut best_solution = start.solution;
+    let mut temp = start.t0;
+    let mut progress = SearchProgress::new();
+    let mut remaining = max_iter;
+
+    while remaining > 0 {
+        let iters = segment_len.min(remaining);
+
+        let (segment_cost, segment_solution, segment_progress) =
+            search.run(problem, best_solution.clone(), iters, temp, start.alpha);
+
+        progress.cache_lookups += segment_progress.cache_lookups;
+        progress.cache_hits += segment_progress.cache_hits;
+
+        if segment_cost < best_cost {
+            best_cost = segment_cost;
+            best_solution = segment_solution;
+        }
+
+        temp *= start.alpha.powi(iters as i32);
+        remaining -= iters;
+
+        let caller_hash = best_solution.route_hash(problem);
+
+        {
+            let mut post_board = post_board.lock().unwrap();
+            post_board.update(best_cost, &best_solution, caller_hash);
         }
 
-        (best_cost, best_solution)
+        let (migrated_solution, migrated_hash) = {
+            let read_board = read_board.lock().unwrap();
+            read_board.best()
+        };
+
+        if migrated_hash != caller_hash && thread_rng.random_bool(migration_probability) {
+            best_solution = migrated_solution;
+        }
     }
+
+    (best_cost, best_solution, progress)
 }
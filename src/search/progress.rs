@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use crate::solution::Solution;
 use crate::types::Cost;
 
@@ -18,6 +20,15 @@ pub struct SearchProgress {
     pub candidate_frequency: HashMap<u64, usize>,
     /// Hash of the current candidate solution
     pub candidate_hash: u64,
+    /// Number of accept/reject decisions made so far.
+    pub decisions: usize,
+    /// Number of those decisions that accepted the candidate.
+    pub accepted: usize,
+    /// Number of visited-solution cache lookups made so far (see
+    /// `Pooled`'s route-hash cache).
+    pub cache_lookups: usize,
+    /// Number of those lookups that hit an already-evaluated solution.
+    pub cache_hits: usize,
 }
 
 impl SearchProgress {
@@ -29,9 +40,55 @@ impl SearchProgress {
             incumbent_cost: 0,
             candidate_frequency: HashMap::new(),
             candidate_hash: 0,
+            decisions: 0,
+            accepted: 0,
+            cache_lookups: 0,
+            cache_hits: 0,
         }
     }
-    
+
+    /// Records whether the main loop's acceptance criterion accepted the
+    /// candidate at this iteration, for `acceptance_rate`.
+    pub fn record_acceptance(&mut self, accepted: bool) {
+        self.decisions += 1;
+        if accepted {
+            self.accepted += 1;
+        }
+    }
+
+    pub fn acceptance_rate(&self) -> f32 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.accepted as f32 / self.decisions as f32
+        }
+    }
+
+    /// Records whether a visited-solution cache lookup hit an
+    /// already-evaluated route hash, for `cache_hit_rate`.
+    pub fn record_cache_lookup(&mut self, hit: bool) {
+        self.cache_lookups += 1;
+        if hit {
+            self.cache_hits += 1;
+        }
+    }
+
+    pub fn cache_hit_rate(&self) -> f32 {
+        if self.cache_lookups == 0 {
+            0.0
+        } else {
+            self.cache_hits as f32 / self.cache_lookups as f32
+        }
+    }
+
+    /// Iterations since the last call to `update_best`, i.e. the current stall length.
+    pub fn stall(&self, iteration: usize) -> usize {
+        match self.best_iterations.last() {
+            Some(&last) => iteration.saturating_sub(last),
+            None => iteration,
+        }
+    }
+
     pub fn record_candidate(&mut self, iteration: usize, solution: &Solution) {
         self.iteration = iteration;
         
@@ -57,3 +114,56 @@ impl SearchProgress {
         self.incumbent_cost = incumbent_cost;
     }
 }
+
+/// Status snapshot passed to a `ProgressHook` callback, mirroring ED_LRR's
+/// periodic `SearchState` callback.
+#[derive(Debug, Clone)]
+pub struct SearchStatus {
+    /// Id of the island (parallel annealing chain) reporting this status.
+    pub island: usize,
+    pub iteration: usize,
+    pub elapsed: Duration,
+    pub incumbent_cost: Cost,
+    pub best_cost: Cost,
+    /// Fraction of all accept/reject decisions so far that accepted the candidate.
+    pub acceptance_rate: f32,
+    /// The acceptance criterion's current decaying parameter, if it has one.
+    pub temperature: Option<f32>,
+    pub operator_weights: Vec<f32>,
+}
+
+/// Returned by a `ProgressHook` callback to continue or cut the search short.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchControl {
+    Continue,
+    Abort,
+}
+
+/// A user callback invoked every `interval` iterations with a `SearchStatus`
+/// snapshot. Wrapped in `Arc<Mutex<_>>` so every island can share and call the
+/// same callback without needing one per thread.
+#[derive(Clone)]
+pub struct ProgressHook {
+    pub interval: usize,
+    pub callback: Arc<Mutex<dyn FnMut(&SearchStatus) -> SearchControl + Send>>,
+}
+
+impl ProgressHook {
+    pub fn new(interval: usize, callback: impl FnMut(&SearchStatus) -> SearchControl + Send + 'static) -> Self {
+        ProgressHook {
+            interval: interval.max(1),
+            callback: Arc::new(Mutex::new(callback)),
+        }
+    }
+}
+
+/// Stop conditions checked every main-loop iteration in addition to `max_iter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StopConditions {
+    /// Abort once this much wall-clock time has elapsed since the run started.
+    pub wall_clock_budget: Option<Duration>,
+    /// Abort after this many iterations in a row with no call to `update_best`.
+    pub stall_limit: Option<usize>,
+    /// Abort once `best_cost` reaches or beats this target.
+    pub target_cost: Option<Cost>,
+}
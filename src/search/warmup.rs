@@ -38,7 +38,7 @@ impl<'a> Warmup<'a> {
 
             // Generate a candidate solution
             let mut candidate = incumbent.clone();
-            let mut calls_to_remove = removal_op_fn(&candidate, &REMOVAL_PARAMS);
+            let mut calls_to_remove = removal_op_fn(&candidate, problem, &mut thread_rng, &REMOVAL_PARAMS);
             if calls_to_remove.is_empty() {
                 calls_to_remove = candidate
                     .call_assignments()
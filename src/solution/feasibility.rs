@@ -1,11 +1,20 @@
 use crate::problem::Problem;
 use crate::solution::route::SimulationResult;
 use crate::solution::Solution;
-use crate::types::{CallId, Capacity, Time, VehicleId};
+use crate::types::{CallId, Capacity, Cost, Time, VehicleId};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::ops::RangeInclusive;
 
 /// An iterator that yields feasible insertion point combinations (pickup_idx, delivery_idx)
-/// while respecting time windows
+/// while respecting time windows.
+///
+/// This is the actual hot insertion path (`operators::insertion` reaches it
+/// through `Solution::get_feasible_insertions`): `new` pays for one
+/// `Route::simulate` to get `times`/`waiting`/`slack`/`min_slack` and the
+/// capacity ranges, then every `(pickup_idx, delivery_idx)` candidate is
+/// judged in O(1) against those cached arrays by `is_insertion_time_feasible`
+/// below, instead of re-simulating the whole route per candidate.
 pub struct FeasibleInsertions<'a> {
     problem: &'a Problem,
     vehicle: VehicleId,
@@ -18,6 +27,10 @@ pub struct FeasibleInsertions<'a> {
     current_delivery_idx: usize,
     max_pickup_idx: usize,
     max_delivery_idx: usize,
+    /// A `(pickup_idx, delivery_idx)` pair this call is pinned to by a
+    /// `Fixed` lock, if any; consumed by the first `next()` call instead of
+    /// the usual range walk (see `problem::locks::LockSet::fixed_position`).
+    fixed: Option<(usize, usize)>,
 }
 
 impl<'a> FeasibleInsertions<'a> {
@@ -32,6 +45,10 @@ impl<'a> FeasibleInsertions<'a> {
             return None;
         }
 
+        if !problem.locks.is_vehicle_allowed(call, vehicle) {
+            return None;
+        }
+
         let route = &solution.routes()[vehicle.index()];
         let route_calls = route.route();
 
@@ -49,6 +66,12 @@ impl<'a> FeasibleInsertions<'a> {
             if !capacity_ranges.is_empty() {
                 let first_range_start = *capacity_ranges[0].1.start();
 
+                let fixed = problem
+                    .locks
+                    .fixed_position(call)
+                    .filter(|&(lock_vehicle, _, _)| lock_vehicle == vehicle)
+                    .map(|(_, pickup_idx, delivery_idx)| (pickup_idx, delivery_idx));
+
                 return Some(Self {
                     problem,
                     vehicle,
@@ -61,6 +84,7 @@ impl<'a> FeasibleInsertions<'a> {
                     current_delivery_idx: first_range_start,
                     max_pickup_idx,
                     max_delivery_idx,
+                    fixed,
                 });
             }
         }
@@ -73,6 +97,49 @@ impl<'a> Iterator for FeasibleInsertions<'a> {
     type Item = (usize, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some((pickup_idx, delivery_idx)) = self.fixed.take() {
+            // A fixed position yields at most this one pair; make sure a
+            // subsequent call falls straight into the exhausted-ranges check
+            // below instead of resuming the normal range walk.
+            self.current_range_idx = self.capacity_ranges.len();
+
+            if self.problem.locks.is_anchored_start(self.call) && pickup_idx != 0 {
+                return None;
+            }
+
+            if self.problem.locks.is_anchored_end(self.call) && delivery_idx != self.route_calls.len() {
+                return None;
+            }
+
+            if self
+                .problem
+                .locks
+                .sequence_violation(&self.route_calls, self.call, pickup_idx)
+            {
+                return None;
+            }
+
+            let capacity_feasible = self
+                .capacity_ranges
+                .iter()
+                .any(|(_, range)| range.contains(&pickup_idx) && range.contains(&delivery_idx));
+            if !capacity_feasible {
+                return None;
+            }
+
+            let is_feasible = is_insertion_time_feasible(
+                self.problem,
+                &self.route_calls,
+                self.simulation,
+                self.vehicle,
+                self.call,
+                pickup_idx,
+                delivery_idx,
+            );
+
+            return if is_feasible { Some((pickup_idx, delivery_idx)) } else { None };
+        }
+
         loop {
             // Check if we've exhausted all ranges
             if self.current_range_idx >= self.capacity_ranges.len() {
@@ -113,6 +180,22 @@ impl<'a> Iterator for FeasibleInsertions<'a> {
             let delivery_idx = self.current_delivery_idx;
             self.current_delivery_idx += 1;
 
+            if self.problem.locks.is_anchored_start(self.call) && pickup_idx != 0 {
+                continue;
+            }
+
+            if self.problem.locks.is_anchored_end(self.call) && delivery_idx != self.route_calls.len() {
+                continue;
+            }
+
+            if self
+                .problem
+                .locks
+                .sequence_violation(&self.route_calls, self.call, pickup_idx)
+            {
+                continue;
+            }
+
             // Check time feasibility
             let is_feasible = is_insertion_time_feasible(
                 self.problem,
@@ -131,16 +214,115 @@ impl<'a> Iterator for FeasibleInsertions<'a> {
     }
 }
 
-fn is_insertion_time_feasible(
+impl<'a> FeasibleInsertions<'a> {
+    /// Bounds this iterator to at most `width` candidate positions instead of
+    /// every feasible `(pickup_idx, delivery_idx)` pair, for routes where the
+    /// full quadratic scan dominates runtime. Drains the ordinary feasible-
+    /// position walk while keeping only the `width` best by `beam_score`
+    /// (lower is better -- tight-slack/high-detour positions are pruned
+    /// first) in a bounded max-heap, evicting the current worst kept
+    /// position whenever a better one is found once the heap is full, then
+    /// drains that heap in ascending score order. An anytime, tunable
+    /// trade of solution quality for speed on large instances.
+    pub fn with_beam(mut self, width: usize) -> BeamInsertions {
+        let mut heap: BinaryHeap<BeamEntry> = BinaryHeap::with_capacity(width);
+
+        while let Some((pickup_idx, delivery_idx)) = self.next() {
+            let delta = insertion_time_delta(self.problem, &self.route_calls, self.vehicle, self.call, pickup_idx, delivery_idx);
+            let slack = available_slack(self.simulation, pickup_idx);
+            let entry = BeamEntry {
+                score: beam_score(delta, slack),
+                pickup_idx,
+                delivery_idx,
+            };
+
+            if heap.len() < width {
+                heap.push(entry);
+            } else if let Some(worst) = heap.peek() {
+                if entry < *worst {
+                    heap.pop();
+                    heap.push(entry);
+                }
+            }
+        }
+
+        // `into_sorted_vec` returns ascending order by `BeamEntry`'s `Ord`,
+        // which is a plain ascending comparison on `score` -- so this is
+        // already cheapest (lowest score) first, the same direction
+        // `peek`/`pop` use to evict the current worst (highest score) entry.
+        let positions: Vec<(usize, usize)> = heap.into_sorted_vec().into_iter().map(|e| (e.pickup_idx, e.delivery_idx)).collect();
+
+        BeamInsertions { positions: positions.into_iter() }
+    }
+}
+
+/// One candidate kept by `FeasibleInsertions::with_beam`'s bounded heap.
+/// Ordered by `score` ascending, so a `BinaryHeap`, which is a max-heap,
+/// surfaces the current worst (highest-score) kept candidate at its top for
+/// cheap eviction.
+struct BeamEntry {
+    score: f64,
+    pickup_idx: usize,
+    delivery_idx: usize,
+}
+
+impl PartialEq for BeamEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+
+impl Eq for BeamEntry {}
+
+impl PartialOrd for BeamEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BeamEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.partial_cmp(&other.score).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Yields the bounded set of candidate positions `FeasibleInsertions::with_beam`
+/// kept, cheapest (lowest `beam_score`) first.
+pub struct BeamInsertions {
+    positions: std::vec::IntoIter<(usize, usize)>,
+}
+
+impl Iterator for BeamInsertions {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.positions.next()
+    }
+}
+
+/// Cheap score `FeasibleInsertions::with_beam` ranks candidate
+/// positions by: `delta` (the extra travel time `is_insertion_time_feasible`
+/// computes for this position) divided by `slack` (the remaining time-window
+/// slack at that position). A position that would consume most of its slack
+/// scores high and is pruned first; one with slack to spare scores low and
+/// survives. `slack` is floored at `1` so a near-zero-slack position still
+/// produces a finite (very large) score instead of dividing by zero.
+fn beam_score(delta: Time, slack: Time) -> f64 {
+    delta as f64 / slack.max(1) as f64
+}
+
+/// Just the `delta` half of `is_insertion_time_feasible`'s time-feasibility
+/// check -- the extra travel time this insertion would add at
+/// `(pickup_idx, delivery_idx)` -- factored out so `with_beam` can score a
+/// position without duplicating the node-lookup arithmetic.
+fn insertion_time_delta(
     problem: &Problem,
     route_calls: &[CallId],
-    sim: &SimulationResult,
     vehicle: VehicleId,
     call: CallId,
     pickup_idx: usize,
     delivery_idx: usize,
-) -> bool {
-    // Determine predecessor node for pickup
+) -> Time {
     let p_node = if pickup_idx == 0 || route_calls.is_empty() {
         problem.get_vehicle(vehicle).home_node
     } else if pickup_idx <= route_calls.len() {
@@ -151,7 +333,6 @@ fn is_insertion_time_feasible(
             problem.destination_node(prev_call)
         }
     } else {
-        // Handle out-of-bounds pickup_idx
         let last_call = *route_calls.last().unwrap();
         if last_call.is_pickup() {
             problem.origin_node(last_call)
@@ -160,7 +341,6 @@ fn is_insertion_time_feasible(
         }
     };
 
-    // Determine successor node for delivery
     let d_node = if route_calls.is_empty() {
         problem.get_vehicle(vehicle).home_node
     } else if delivery_idx < route_calls.len() {
@@ -171,37 +351,277 @@ fn is_insertion_time_feasible(
             problem.destination_node(next_call)
         }
     } else {
-        // For delivery at the end of the route
         problem.get_vehicle(vehicle).home_node
     };
 
-    // Compute the "original" travel time
     let orig_time = problem.get_travel_time(vehicle, p_node, d_node);
 
-    // Compute new travel time with the inserted call
     let new_pickup = problem.origin_node(call);
     let new_delivery = problem.destination_node(call);
 
-    // Calculate the new path times
     let new_time = problem.get_travel_time(vehicle, p_node, new_pickup)
         + problem.service_time(vehicle, call.pickup())
         + problem.get_travel_time(vehicle, new_pickup, new_delivery)
         + problem.service_time(vehicle, call.delivery())
         + problem.get_travel_time(vehicle, new_delivery, d_node);
 
-    // Calculate the extra time required
-    let delta = new_time.saturating_sub(orig_time);
+    new_time.saturating_sub(orig_time)
+}
 
-    // Check if there's enough slack at this position
-    let available_slack = if pickup_idx < sim.min_slack.len() {
+/// The remaining time-window slack available at `pickup_idx`, the same
+/// lookup `is_insertion_time_feasible` makes against `sim.min_slack`.
+fn available_slack(sim: &SimulationResult, pickup_idx: usize) -> Time {
+    if pickup_idx < sim.min_slack.len() {
         sim.min_slack[pickup_idx]
     } else if sim.min_slack.is_empty() {
-        // For empty routes
         Time::MAX
     } else {
-        // For insertions at the end
         sim.min_slack[sim.min_slack.len() - 1]
-    };
+    }
+}
+
+/// Like `Solution::get_feasible_insertions`, but scores every feasible
+/// `(pickup_idx, delivery_idx)` pair with `insertion_delta_cost` and returns
+/// them cheapest-first instead of in enumeration order. Used by
+/// `RegretConstructor` to rank a call's candidate positions without paying
+/// for a full trial-insert/`Solution::cost`/remove cycle per candidate.
+pub fn ranked_insertions(
+    problem: &Problem,
+    solution: &Solution,
+    vehicle: VehicleId,
+    call: CallId,
+    capacity_result: &Option<crate::solution::route::CapacityResult>,
+) -> Vec<(usize, usize, Cost)> {
+    let route = &solution.routes()[vehicle.index()];
+    let route_calls = route.route();
+
+    let mut ranked: Vec<(usize, usize, Cost)> = FeasibleInsertions::new(problem, solution, vehicle, call, capacity_result)
+        .into_iter()
+        .flatten()
+        .map(|(pickup_idx, delivery_idx)| {
+            let delta = insertion_delta_cost(problem, &route_calls, vehicle, call, pickup_idx, delivery_idx);
+            (pickup_idx, delivery_idx, delta)
+        })
+        .collect();
+
+    ranked.sort_by_key(|&(_, _, cost)| cost);
+    ranked
+}
+
+/// Forward-walks `route_calls` for `vehicle` without aborting at the first
+/// violation, tallying the total magnitude of every constraint breach:
+/// capacity overflow (legacy weight dimension) plus time-window lateness,
+/// summed across every stop. Returns `None` if the route is fully feasible,
+/// or `Some((first_violating_index, total_magnitude))` otherwise. Used by
+/// `Solution::repair` to both detect infeasibility and score how much
+/// ejecting a candidate call would relieve it.
+pub(crate) fn route_violation(problem: &Problem, vehicle: VehicleId, route_calls: &[CallId]) -> Option<(usize, i64)> {
+    let veh = problem.get_vehicle(vehicle);
+    let mut current_time = veh.starting_time;
+    let mut load: Capacity = 0;
+    let mut total: i64 = 0;
+    let mut first_violation = None;
+
+    for (i, &call) in route_calls.iter().enumerate() {
+        let leg_time = if i == 0 {
+            let destination = if call.is_pickup() {
+                problem.origin_node(call)
+            } else {
+                problem.destination_node(call)
+            };
+            problem.get_first_travel_time(vehicle, destination)
+        } else {
+            problem.travel_time_between_calls(vehicle, route_calls[i - 1], call)
+        };
+        current_time = current_time.saturating_add(leg_time);
+
+        if call.is_pickup() {
+            load += problem.cargo_size(call) as Capacity;
+        } else {
+            load -= problem.cargo_size(call) as Capacity;
+        }
+        if load > veh.capacity {
+            total += (load - veh.capacity) as i64;
+            first_violation.get_or_insert(i);
+        }
+
+        let waiting = problem.waiting_time(current_time, call);
+        if waiting > 0 {
+            current_time = current_time.saturating_add(waiting);
+        } else {
+            let window_end = *problem.time_window(call).end();
+            if current_time > window_end {
+                total += (current_time - window_end) as i64;
+                first_violation.get_or_insert(i);
+            }
+        }
+
+        current_time = current_time.saturating_add(problem.service_time(vehicle, call));
+    }
+
+    first_violation.map(|idx| (idx, total))
+}
+
+/// The time-feasibility half of a candidate insertion: a positive "push"
+/// (the extra travel/service time this insertion would add at the
+/// insertion point) is feasible iff it fits within `min_slack` at the
+/// following position -- the reverse-pass quantity that already accounts for
+/// how much of the push downstream waiting time would absorb. O(1) given
+/// `sim`, no re-`simulate` required.
+fn is_insertion_time_feasible(
+    problem: &Problem,
+    route_calls: &[CallId],
+    sim: &SimulationResult,
+    vehicle: VehicleId,
+    call: CallId,
+    pickup_idx: usize,
+    delivery_idx: usize,
+) -> bool {
+    let delta = insertion_time_delta(problem, route_calls, vehicle, call, pickup_idx, delivery_idx);
+    let available_slack = available_slack(sim, pickup_idx);
 
     delta <= available_slack
+}
+
+/// Estimates the cost of inserting `call` into `vehicle`'s route at
+/// `(pickup_idx, delivery_idx)`: the same before/after travel arithmetic as
+/// `is_insertion_time_feasible` (travel cost rather than travel time of
+/// routing through the inserted call instead of straight past it), plus the
+/// call's fixed port handling cost. Like its time-feasibility counterpart,
+/// this only accounts for the detour at the insertion point itself, not any
+/// knock-on change to soft penalties elsewhere in the route.
+fn insertion_delta_cost(
+    problem: &Problem,
+    route_calls: &[CallId],
+    vehicle: VehicleId,
+    call: CallId,
+    pickup_idx: usize,
+    delivery_idx: usize,
+) -> Cost {
+    // Determine predecessor node for pickup
+    let p_node = if pickup_idx == 0 || route_calls.is_empty() {
+        problem.get_vehicle(vehicle).home_node
+    } else if pickup_idx <= route_calls.len() {
+        let prev_call = route_calls[pickup_idx - 1];
+        if prev_call.is_pickup() {
+            problem.origin_node(prev_call)
+        } else {
+            problem.destination_node(prev_call)
+        }
+    } else {
+        let last_call = *route_calls.last().unwrap();
+        if last_call.is_pickup() {
+            problem.origin_node(last_call)
+        } else {
+            problem.destination_node(last_call)
+        }
+    };
+
+    // Determine successor node for delivery
+    let d_node = if route_calls.is_empty() {
+        problem.get_vehicle(vehicle).home_node
+    } else if delivery_idx < route_calls.len() {
+        let next_call = route_calls[delivery_idx];
+        if next_call.is_pickup() {
+            problem.origin_node(next_call)
+        } else {
+            problem.destination_node(next_call)
+        }
+    } else {
+        problem.get_vehicle(vehicle).home_node
+    };
+
+    let orig_cost = problem.get_travel_cost(vehicle, p_node, d_node);
+
+    let new_pickup = problem.origin_node(call);
+    let new_delivery = problem.destination_node(call);
+
+    let new_cost = problem.get_travel_cost(vehicle, p_node, new_pickup)
+        + problem.get_travel_cost(vehicle, new_pickup, new_delivery)
+        + problem.get_travel_cost(vehicle, new_delivery, d_node);
+
+    (new_cost - orig_cost) + problem.port_cost_for_call(vehicle, call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::index::ProblemIndex;
+    use crate::problem::locks::LockSet;
+    use crate::problem::{CallParameters, Problem, TimeWindowPolicy, Vehicle};
+    use crate::utils::{Matrix2, Matrix3};
+
+    /// A one-vehicle problem over `travel_cost`'s nodes (node 0 is the
+    /// vehicle's home depot), one call per `(origin, destination)` pair in
+    /// `calls`, with `travel_time` left at zero and wide time windows so no
+    /// time-window check would block the position under test -- only
+    /// `insertion_delta_cost`'s cost arithmetic is exercised here.
+    fn cost_test_problem(n_nodes: usize, travel_cost: &[(usize, usize, Cost)], calls: &[(usize, usize)]) -> Problem {
+        let n_calls = calls.len();
+        let mut cost_matrix = Matrix3::new(1, n_nodes, n_nodes, 0);
+        for &(from, to, cost) in travel_cost {
+            *cost_matrix.get_mut(0, from, to) = cost;
+        }
+
+        let problem_calls = calls
+            .iter()
+            .map(|&(origin, destination)| CallParameters {
+                origin: origin as crate::types::NodeId,
+                destination: destination as crate::types::NodeId,
+                size: 1,
+                not_transport_cost: 0,
+                pickup_window: 0..=1_000,
+                delivery_window: 0..=1_000,
+            })
+            .collect();
+
+        Problem {
+            n_nodes: n_nodes as crate::types::NodeId,
+            n_vehicles: VehicleId::new(1).unwrap(),
+            n_calls: CallId::new_pickup(n_calls as i16).unwrap(),
+            vehicles: vec![Vehicle { home_node: 0, starting_time: 0, capacity: 1_000_000 }],
+            calls: problem_calls,
+            travel_time: Matrix3::new(1, n_nodes, n_nodes, 0),
+            travel_cost: cost_matrix,
+            vessel_cargo: Matrix2::new(1, n_calls, true),
+            loading_time: Matrix2::new(1, n_calls, 0),
+            unloading_time: Matrix2::new(1, n_calls, 0),
+            port_cost: Matrix2::new(1, n_calls, 0),
+            index: ProblemIndex::default(),
+            locks: LockSet::default(),
+            return_to_depot: false,
+            dimensions: Vec::new(),
+            time_window_policy: TimeWindowPolicy::default(),
+        }
+    }
+
+    #[test]
+    fn insertion_delta_cost_into_an_empty_route_is_depot_round_trip() {
+        // depot(0) -> origin(1) -> destination(2) -> depot(0).
+        let problem = cost_test_problem(3, &[(0, 1, 10), (1, 2, 5), (2, 0, 7)], &[(1, 2)]);
+        let vehicle = VehicleId::new(1).unwrap();
+        let call = CallId::new_pickup(1).unwrap();
+
+        let delta = insertion_delta_cost(&problem, &[], vehicle, call, 0, 0);
+
+        assert_eq!(delta, 10 + 5 + 7);
+    }
+
+    #[test]
+    fn insertion_delta_cost_ranks_the_cheaper_of_two_detours_lower() {
+        // A route visiting call A's pickup(1)/delivery(2); call B (origin 3,
+        // destination 4) can be inserted at the front, ahead of call A, at
+        // either a cheap or an expensive detour cost from the depot.
+        let vehicle = VehicleId::new(1).unwrap();
+        let route_calls = vec![CallId::new_pickup(1).unwrap(), CallId::new_pickup(1).unwrap().delivery()];
+        let call_b = CallId::new_pickup(2).unwrap();
+
+        let cheap = cost_test_problem(5, &[(0, 1, 10), (0, 3, 1), (3, 4, 1), (4, 1, 1)], &[(1, 2), (3, 4)]);
+        let expensive = cost_test_problem(5, &[(0, 1, 10), (0, 3, 100), (3, 4, 100), (4, 1, 100)], &[(1, 2), (3, 4)]);
+
+        let cheap_delta = insertion_delta_cost(&cheap, &route_calls, vehicle, call_b, 0, 0);
+        let expensive_delta = insertion_delta_cost(&expensive, &route_calls, vehicle, call_b, 0, 0);
+
+        assert!(cheap_delta < expensive_delta);
+    }
 }
\ No newline at end of file
@@ -2,10 +2,11 @@ mod route;
 mod solution;
 
 pub(crate) use route::Route;
-pub use solution::Solution;
+pub use solution::{CompletionObjective, ConstructionMode, Solution};
 
 mod compact;
 mod feasibility;
+pub mod objectives;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,93 @@
+use std::cmp::Ordering;
+
+use crate::problem::Problem;
+use crate::types::{CallId, Cost, VehicleId};
+
+use super::Solution;
+
+/// Number of objectives `evaluate` returns.
+pub const N_OBJECTIVES: usize = 3;
+
+/// Computes a fixed-length, minimized objective vector for `solution`:
+/// `[0]` total transportation + port cost, `[1]` total not-transport penalty
+/// of unassigned calls, and `[2]` the number of vehicles with a non-empty
+/// route. Mirrors the TotalCost/TotalRoutes/TotalUnassignedJobs
+/// decomposition common in rich-VRP solvers, so a downstream metaheuristic
+/// can maintain a Pareto archive (see `dominance_order`) instead of tuning a
+/// single weighted sum.
+pub fn evaluate(solution: &mut Solution, problem: &Problem) -> [Cost; N_OBJECTIVES] {
+    let not_transport_cost: Cost = solution
+        .call_assignments()
+        .iter()
+        .enumerate()
+        .filter(|(_, assignment)| assignment.is_none())
+        .map(|(i, _)| {
+            let call = CallId::new_pickup((i + 1) as i16).expect("CallId should be nonzero");
+            problem.not_transport_cost(call)
+        })
+        .sum();
+
+    // `cost` bundles the not-transport penalty into its total, so subtract
+    // it back out to isolate the transportation + port cost objective.
+    let transport_cost = solution.cost(problem) - not_transport_cost;
+
+    let n_vehicles_used = (1..=problem.n_vehicles.get())
+        .filter(|&v| {
+            let vehicle = VehicleId::new(v).expect("VehicleId must be nonzero");
+            !solution.route(vehicle).is_empty()
+        })
+        .count() as Cost;
+
+    [transport_cost, not_transport_cost, n_vehicles_used]
+}
+
+/// Compares two minimized objective vectors for Pareto dominance: `Less` if
+/// `a` dominates `b` (every objective in `a` is `≤` the corresponding one in
+/// `b`, and strictly `<` in at least one), `Greater` in the symmetric case
+/// where `b` dominates `a`, and `Equal` when neither dominates the other
+/// (they're incomparable, or identical).
+pub fn dominance_order(a: &[Cost], b: &[Cost]) -> Ordering {
+    let mut a_better = false;
+    let mut b_better = false;
+
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        match x.cmp(&y) {
+            Ordering::Less => a_better = true,
+            Ordering::Greater => b_better = true,
+            Ordering::Equal => {}
+        }
+    }
+
+    match (a_better, b_better) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominance_order_strictly_better_in_every_objective() {
+        assert_eq!(dominance_order(&[1, 2, 3], &[2, 3, 4]), Ordering::Less);
+        assert_eq!(dominance_order(&[2, 3, 4], &[1, 2, 3]), Ordering::Greater);
+    }
+
+    #[test]
+    fn dominance_order_better_in_one_equal_in_the_rest() {
+        assert_eq!(dominance_order(&[1, 2, 3], &[1, 2, 4]), Ordering::Less);
+    }
+
+    #[test]
+    fn dominance_order_identical_vectors_are_incomparable() {
+        assert_eq!(dominance_order(&[1, 2, 3], &[1, 2, 3]), Ordering::Equal);
+    }
+
+    #[test]
+    fn dominance_order_mixed_tradeoff_is_incomparable() {
+        // Better in objective 0, worse in objective 1: neither dominates.
+        assert_eq!(dominance_order(&[1, 5], &[2, 4]), Ordering::Equal);
+    }
+}
@@ -1,6 +1,6 @@
-use crate::problem::Problem;
+use crate::problem::{Problem, TimeWindowPolicy};
 use crate::solution::compact::CompactIter;
-use crate::types::{CallId, Capacity, CargoSize, Cost, Time, VehicleId};
+use crate::types::{CallId, Capacity, Cost, Time, VehicleId};
 use std::ops::RangeInclusive;
 use crate::solution::solution::CallCost;
 
@@ -17,10 +17,29 @@ pub struct SimulationResult {
     pub waiting: Vec<Time>,
     pub slack: Vec<Time>,
     pub min_slack: Vec<Time>, // reverse pass: min slack from index to the end of the route
-    pub loads: Vec<Capacity>,
+    /// Running load after each stop, one entry per capacity dimension: index
+    /// 0 is the legacy weight dimension, followed by `problem.dimensions` in
+    /// order.
+    pub loads: Vec<Vec<Capacity>>,
+    /// Reverse-pass analogue of `min_slack`, one entry per dimension: the
+    /// maximum load seen from position `i` to the end of the route. Lets a
+    /// span query know the worst load it could possibly run into ahead of
+    /// a point in O(1), instead of re-scanning `loads`.
+    pub max_load_ahead: Vec<Vec<Capacity>>,
+    /// Forward-pass counterpart to `max_load_ahead`: the maximum load seen
+    /// from the start of the route up to and including position `i`.
+    pub max_load_behind: Vec<Vec<Capacity>>,
     pub capacity: Option<CapacityResult>,
     pub route_cost: Cost,
     pub port_cost: Cost,
+    /// When the vehicle finishes its last stop, including the return-to-depot
+    /// leg if `problem.return_to_depot` is set. The minimize-arrival-time
+    /// objective (see `Solution::completion_time`) is built on this.
+    pub completion_time: Time,
+    /// Accumulated cost from time-window violations under
+    /// `TimeWindowPolicy::Soft`; zero under the default `Hard` policy, where
+    /// a violation aborts the route instead.
+    pub soft_penalty: Cost,
     pub is_feasible: bool,
     pub infeasible_at: Option<usize>, // index of the call where infeasibility was detected
     pub error: Option<String>,
@@ -136,6 +155,11 @@ impl Route {
     /// If a constraint is violated (e.g. vessel incompatibility or capacity exceeded),
     /// the simulation stops early and marks the route as infeasible
     pub fn simulate(&mut self, problem: &Problem, vehicle: VehicleId, mut call_costs: Option<&mut Vec<CallCost>>) -> bool {
+        // For vehicle lookup; vehicles are stored in order so that route at index i
+        // corresponds to vehicle with id = (i+1).
+        let veh_idx = vehicle.index();
+        let veh = &problem.vehicles[veh_idx];
+
         if self.is_empty() {
             self.simulation = Some(SimulationResult {
                 times: vec![],
@@ -143,9 +167,13 @@ impl Route {
                 slack: vec![],
                 min_slack: vec![],
                 loads: vec![],
+                max_load_ahead: vec![],
+                max_load_behind: vec![],
                 capacity: None,
                 route_cost: 0,
                 port_cost: 0,
+                completion_time: veh.starting_time,
+                soft_penalty: 0,
                 is_feasible: true,
                 infeasible_at: None,
                 error: None,
@@ -154,19 +182,20 @@ impl Route {
             return true;
         }
 
-        // For vehicle lookup; vehicles are stored in order so that route at index i
-        // corresponds to vehicle with id = (i+1).
-        let veh_idx = vehicle.index();
-        let veh = &problem.vehicles[veh_idx];
-        let max_capacity = veh.capacity;
+        // Dimension 0 is the legacy weight dimension (`CallParameters::size` /
+        // `Vehicle::capacity`); any `problem.dimensions` follow it.
+        let bounds: Vec<Capacity> = std::iter::once(veh.capacity)
+            .chain(problem.dimensions.iter().map(|dim| dim.bound(vehicle)))
+            .collect();
 
         // Start at the depot node.
         let mut previous_node = veh.home_node;
         let mut route_cost: Cost = 0;
         let mut port_cost: Cost = 0;
+        let mut soft_penalty: Cost = 0;
 
         let mut current_time: Time = veh.starting_time;
-        let mut current_load: i32 = 0;
+        let mut current_loads: Vec<Capacity> = vec![0; bounds.len()];
 
         let mut times = Vec::with_capacity(self.len());
         let mut waiting = Vec::with_capacity(self.len());
@@ -266,26 +295,38 @@ impl Route {
             // Update previous node.
             previous_node = call_node;
 
-            // Update load.
-            // For a pickup, add cargo size; for a delivery, subtract it.
-            let size = problem.cargo_size(call) as i32;
-            if call.is_pickup() {
-                current_load += size;
-            } else {
-                current_load -= size;
+            // Update every dimension's running load: dimension 0 is the
+            // legacy weight (`cargo_size`), the rest come from
+            // `problem.dimensions`, each added at pickup and removed at the
+            // paired delivery.
+            let magnitudes = std::iter::once(problem.cargo_size(call) as Capacity)
+                .chain(problem.dimensions.iter().map(|dim| dim.magnitude(call)));
+
+            let mut exceeded = None;
+            for (d, magnitude) in magnitudes.enumerate() {
+                if call.is_pickup() {
+                    current_loads[d] += magnitude;
+                } else {
+                    current_loads[d] -= magnitude;
+                }
+
+                if current_loads[d] > bounds[d] && exceeded.is_none() {
+                    exceeded = Some(d);
+                }
             }
 
-            // Check capacity.
-            if current_load > max_capacity {
+            // Check capacity: infeasible if *any* dimension exceeds its bound.
+            if let Some(d) = exceeded {
+                let dim_name: &str = if d == 0 { "weight" } else { problem.dimensions[d - 1].name.as_str() };
                 feasible = false;
                 error = Some(format!(
-                    "Capacity exceeded on call {:?}: load {} > capacity {}",
-                    call, current_load, max_capacity
+                    "Capacity exceeded on call {:?}: dimension {:?} load {} > bound {}",
+                    call, dim_name, current_loads[d], bounds[d]
                 ));
                 infeasible_at = Some(i);
                 break;
             }
-            loads.push(current_load);
+            loads.push(current_loads.clone());
 
             let time_window = problem.time_window(call);
 
@@ -297,22 +338,41 @@ impl Route {
             let waiting_time = time_window.start().saturating_sub(current_time);
             waiting.push(waiting_time);
 
-            // Check time window.
+            // Check time window. Under the default `TimeWindowPolicy::Hard`, a
+            // violation aborts the route exactly as before. Under `Soft`, an
+            // early arrival with `early_penalty` set departs immediately
+            // instead of waiting (charging the earliness), and a late
+            // arrival charges `late_penalty` instead of breaking, so the
+            // route keeps being simulated to completion.
             if waiting_time > 0 {
-                // If the current time is before the call’s lower time window, wait until it opens.
-                current_time = *time_window.start();
+                match problem.time_window_policy {
+                    TimeWindowPolicy::Soft { early_penalty: Some(rate), .. } => {
+                        soft_penalty = soft_penalty.saturating_add(rate.saturating_mul(waiting_time as Cost));
+                    }
+                    _ => {
+                        // If the current time is before the call's lower time window, wait until it opens.
+                        current_time = *time_window.start();
+                    }
+                }
             } else if slack_time < 0 {
-                // If the current time is after the call’s upper time window, the route is infeasible.
-                feasible = false;
-                error = Some(format!(
-                    "Time window violated on call {:?}: time {} is outside [{}, {}]",
-                    call,
-                    current_time,
-                    time_window.start(),
-                    time_window.end()
-                ));
-                infeasible_at = Some(i);
-                break;
+                match problem.time_window_policy {
+                    TimeWindowPolicy::Hard => {
+                        // If the current time is after the call's upper time window, the route is infeasible.
+                        feasible = false;
+                        error = Some(format!(
+                            "Time window violated on call {:?}: time {} is outside [{}, {}]",
+                            call,
+                            current_time,
+                            time_window.start(),
+                            time_window.end()
+                        ));
+                        infeasible_at = Some(i);
+                        break;
+                    }
+                    TimeWindowPolicy::Soft { late_penalty, .. } => {
+                        soft_penalty = soft_penalty.saturating_add(late_penalty.saturating_mul((-slack_time) as Cost));
+                    }
+                }
             }
 
             // Add service time (loading or unloading) for this call.
@@ -321,6 +381,18 @@ impl Route {
         }
 
         let min_slack = Route::compute_min_remaining_slack(&slack, &waiting);
+        let (max_load_behind, max_load_ahead) = Route::compute_load_profile(&loads, bounds.len());
+
+        // Close the cost model with an explicit return-to-depot leg, if enabled:
+        // charge travel(last_node -> home_node) to route_cost and to the time
+        // the vehicle is considered done, rather than leaving the route open-ended.
+        let mut completion_time = current_time;
+        if feasible && problem.return_to_depot {
+            route_cost += problem.get_travel_cost(vehicle, previous_node, veh.home_node);
+            completion_time = current_time.saturating_add(
+                problem.get_travel_time(vehicle, previous_node, veh.home_node),
+            );
+        }
 
         self.simulation = Some(SimulationResult {
             times,
@@ -328,9 +400,13 @@ impl Route {
             slack,
             min_slack,
             loads,
+            max_load_ahead,
+            max_load_behind,
             capacity: None,
             route_cost,
             port_cost,
+            completion_time,
+            soft_penalty,
             is_feasible: feasible,
             infeasible_at,
             error,
@@ -363,52 +439,135 @@ impl Route {
         min_slack
     }
 
+    /// Forward and reverse running-max profile of `loads`, one entry per
+    /// dimension: `behind[i]` is the max load anywhere in `[0, i]`,
+    /// `ahead[i]` the max load anywhere in `[i, n)`. Analogous to
+    /// `compute_min_remaining_slack`, but for capacity instead of time —
+    /// the "running max future demand" trick capacitated constraint
+    /// checkers use so span-capacity queries don't re-scan the route.
+    fn compute_load_profile(loads: &[Vec<Capacity>], n_dimensions: usize) -> (Vec<Vec<Capacity>>, Vec<Vec<Capacity>>) {
+        let n = loads.len();
+        let mut behind: Vec<Vec<Capacity>> = Vec::with_capacity(n);
+        let mut running = vec![0 as Capacity; n_dimensions];
+        for load in loads {
+            for d in 0..n_dimensions {
+                running[d] = running[d].max(load[d]);
+            }
+            behind.push(running.clone());
+        }
+
+        let mut ahead: Vec<Vec<Capacity>> = vec![vec![0; n_dimensions]; n];
+        let mut running = vec![0 as Capacity; n_dimensions];
+        for i in (0..n).rev() {
+            for d in 0..n_dimensions {
+                running[d] = running[d].max(loads[i][d]);
+            }
+            ahead[i] = running.clone();
+        }
+
+        (behind, ahead)
+    }
+
     pub(super) fn last_simulation(&self) -> Option<&SimulationResult> {
         self.simulation.as_ref()
     }
 
-    /// Given a SimulationResult (with its sim.loads vector) and the call weight required,
-    /// this function returns a vector of continuous ranges along the route (by index)
-    /// where the available capacity (vehicle_capacity - sim.loads[i]) is at least call_weight.
-    /// In other words, it merges candidate indices that are consecutive into ranges,
-    /// and also computes the minimum available capacity within each range.
-    pub(super) fn find_spare_capacity(&mut self, problem: &Problem, call_weight: CargoSize, vehicle: VehicleId) -> &Option<CapacityResult> {
+    /// The minimize-arrival-time objective at the route level: when this
+    /// route's vehicle finishes, per the last `simulate` call. 0 if the route
+    /// hasn't been simulated yet.
+    pub(super) fn completion_time(&self) -> Time {
+        self.simulation.as_ref().map_or(0, |sim| sim.completion_time)
+    }
+
+    /// After a feasible simulation, reclaims idle time by pushing the
+    /// schedule later wherever there is spare forward slack, instead of
+    /// waiting at the earliest feasible instant at every stop. The delay a
+    /// "pushable" segment starting at index `i` can absorb without
+    /// violating any downstream window is exactly `min_slack[i]`; walking
+    /// the route once and tracking that budget (refreshed to `min_slack[i]`
+    /// whenever it opens up further) lets each wait event convert as much
+    /// of itself into a later start as the tightest downstream window
+    /// allows — first uniformly from the route's start, then piecewise
+    /// from wherever an earlier segment's budget ran out. A reclaimed wait
+    /// never pushes a departure past its own window, so `times` stay
+    /// untouched; only `waiting` shrinks. Returns the total waiting time
+    /// reclaimed, so an objective can prefer compact, low-idle schedules.
+    pub(super) fn optimize_schedule(&mut self) -> Time {
+        let Some(sim) = self.simulation.as_mut() else {
+            return 0;
+        };
+
+        if !sim.is_feasible || sim.waiting.is_empty() {
+            return 0;
+        }
+
+        let mut total_reclaimed: Time = 0;
+        let mut budget: Time = 0;
+
+        for i in 0..sim.waiting.len() {
+            budget = budget.max(sim.min_slack[i]);
+
+            if sim.waiting[i] > 0 && budget > 0 {
+                let reclaim = sim.waiting[i].min(budget);
+                sim.waiting[i] -= reclaim;
+                budget -= reclaim;
+                total_reclaimed = total_reclaimed.saturating_add(reclaim);
+            }
+        }
+
+        total_reclaimed
+    }
+
+    /// Given a SimulationResult (with its sim.loads vector) and `call`, this
+    /// function returns a vector of continuous ranges along the route (by
+    /// index) where *every* capacity dimension (weight plus any
+    /// `problem.dimensions`) has enough spare capacity for `call`. In other
+    /// words, it merges candidate indices that are consecutive into ranges.
+    pub(super) fn find_spare_capacity(&mut self, problem: &Problem, call: CallId, vehicle: VehicleId) -> &Option<CapacityResult> {
         if self.simulation.is_none() {
             self.simulate(problem, vehicle, None);
         }
-        
+
         let sim = self.simulation.as_ref().unwrap();
         let vehicle_capacity = problem.get_vehicle(vehicle).capacity;
-        
+
+        let required: Vec<Capacity> = std::iter::once(problem.cargo_size(call) as Capacity)
+            .chain(problem.dimensions.iter().map(|dim| dim.magnitude(call)))
+            .collect();
+        let bounds: Vec<Capacity> = std::iter::once(vehicle_capacity)
+            .chain(problem.dimensions.iter().map(|dim| dim.bound(vehicle)))
+            .collect();
+
+        let fits = |loads: &[Capacity]| {
+            bounds.iter().zip(required.iter()).enumerate().all(|(d, (&bound, &req))| {
+                bound.saturating_sub(loads.get(d).copied().unwrap_or(0)) >= req
+            })
+        };
+
         // Initialize our result vector
         let mut capacity_indices = Vec::new();
-        
-        // Always check capacity at index 0 (before any pickup)
-        if vehicle_capacity >= call_weight as Capacity {
-            capacity_indices.push(0);
-        }
-        
-        // For each position in the route, check if there's enough capacity
-        for i in 0..sim.loads.len() {
-            let available_capacity = vehicle_capacity.saturating_sub(sim.loads[i]);
-            if available_capacity >= call_weight as Capacity {
-                capacity_indices.push(i + 1); // +1 because indices represent positions *after* stops
+
+        // Fast path: if `call` fits even against the worst load seen anywhere
+        // in the route (`max_load_ahead[0]`), it fits everywhere, so every
+        // position is a candidate without scanning `loads` position by
+        // position.
+        let worst_load = sim.max_load_ahead.first().cloned().unwrap_or_else(|| vec![0; bounds.len()]);
+        if fits(&worst_load) {
+            capacity_indices.extend(0..=sim.loads.len());
+        } else {
+            // Always check capacity at index 0 (before any pickup): no load yet.
+            if fits(&vec![0; bounds.len()]) {
+                capacity_indices.push(0);
             }
-        }
-        
-        // For empty routes, add index 0 if not already added
-        if sim.loads.is_empty() && !capacity_indices.contains(&0) && vehicle_capacity >= call_weight as Capacity {
-            capacity_indices.push(0);
-        }
-        
-        // Always consider the end of the route (after the last stop)
-        if !sim.loads.is_empty() && vehicle_capacity >= call_weight as Capacity {
-            let last_idx = sim.loads.len();
-            if !capacity_indices.contains(&last_idx) {
-                capacity_indices.push(last_idx);
+
+            // For each position in the route, check if every dimension fits
+            for i in 0..sim.loads.len() {
+                if fits(&sim.loads[i]) {
+                    capacity_indices.push(i + 1); // +1 because indices represent positions *after* stops
+                }
             }
         }
-        
+
         // Find continuous ranges from the indices
         let mut continuous_ranges = Vec::new();
         
@@ -1,14 +1,147 @@
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
+use crate::operators::ruin_recreate::regret_k_recreate;
+use crate::problem::clustering::ClusterMap;
 use crate::problem::Problem;
-use crate::solution::feasibility::FeasibleInsertions;
+use crate::solution::feasibility::{ranked_insertions, route_violation, FeasibleInsertions};
 use crate::solution::route::CapacityResult;
 use crate::solution::Route;
 use crate::types::*;
+use crate::utils::Matrix2;
 use std::collections::{HashMap, HashSet};
-use std::hash::{Hash, Hasher};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::SplitMix64;
 
+/// Selects the construction heuristic used by [`Solution::construct`], mirroring
+/// ED_LRR's BFS/Greedy/A*-style choices for building an initial solution.
+#[derive(Clone, Copy, Debug)]
+pub enum ConstructionMode {
+    /// Repeatedly inserts the globally cheapest feasible (call, vehicle, position)
+    /// triple until no unassigned call has a feasible insertion left.
+    Greedy,
+    /// Regret-2 insertion: each round, inserts the unassigned call whose best
+    /// vehicle beats its second-best vehicle by the largest margin.
+    Regret2,
+    /// GRASP: each round, ranks every unassigned call by its cheapest feasible
+    /// insertion and picks uniformly at random among the `alpha` cheapest.
+    Grasp { alpha: usize },
+    /// Beam search over whole vehicle routes (see
+    /// `operators::construction::beam_search_calls`), followed by `repair`
+    /// and a regret-2 reinsertion. This is a different procedure from
+    /// `Greedy`, also at `width == 1`; a wider beam keeps more partial
+    /// assignments alive so a locally-cheapest choice doesn't foreclose a
+    /// better one downstream.
+    Beam { width: usize },
+}
+
+/// Regret-`k` greedy construction/repair driven by `Solution::ranked_insertions`
+/// instead of `operators::ruin_recreate::regret_k_recreate`'s trial-insert-
+/// and-measure loop: for each unassigned call, takes its cheapest estimated
+/// insertion cost on every compatible vehicle, ranks the call by the regret
+/// of delaying it (the gap between its best vehicle and the next `k - 1`
+/// cheapest, treating a vehicle with no feasible insertion as near-infinite
+/// regret), and commits the call with the largest regret at its own cheapest
+/// position. Cheaper per candidate than `regret_k_recreate` since it scores
+/// with `ranked_insertions`'s local delta-cost estimate rather than a full
+/// `Solution::cost` recomputation, at the cost of ignoring any soft-penalty
+/// ripple into the rest of the route.
+pub struct RegretConstructor {
+    pub k: usize,
+}
+
+impl RegretConstructor {
+    pub fn new(k: usize) -> Self {
+        RegretConstructor { k: k.max(2) }
+    }
+
+    /// Greedily assigns every currently-unassigned call in `solution`,
+    /// leaving calls with no feasible insertion anywhere in the dummy pool.
+    /// Returns `(evaluated, infeasible)` in the same shape as the other
+    /// insertion operators.
+    pub fn construct(&self, solution: &mut Solution, problem: &Problem) -> (usize, usize) {
+        let mut evaluated = 0;
+        let mut infeasible = 0;
+
+        loop {
+            let unassigned = unassigned_calls(solution);
+            if unassigned.is_empty() {
+                break;
+            }
+
+            let mut best_call: Option<(CallId, VehicleId, usize, usize, Cost, f32)> = None;
+
+            for &call in &unassigned {
+                let mut per_vehicle_best: Vec<(Cost, VehicleId, usize, usize)> = Vec::new();
+
+                for &vehicle in problem.get_compatible_vehicles(call.pickup()) {
+                    let (_, capacity_result) = solution.find_spare_capacity_in_vehicle(problem, call, vehicle);
+                    if capacity_result.is_none() {
+                        continue;
+                    }
+                    let capacity_result = capacity_result.clone();
+
+                    let ranked = solution.ranked_insertions(problem, call, vehicle, &capacity_result);
+                    evaluated += ranked.len();
+
+                    if let Some(&(pickup_idx, delivery_idx, cost)) = ranked.first() {
+                        per_vehicle_best.push((cost, vehicle, pickup_idx, delivery_idx));
+                    } else {
+                        infeasible += 1;
+                    }
+                }
+
+                if per_vehicle_best.is_empty() {
+                    continue;
+                }
+
+                per_vehicle_best.sort_by_key(|&(cost, _, _, _)| cost);
+
+                let (best_cost, best_vehicle, best_pickup, best_delivery) = per_vehicle_best[0];
+
+                // Vehicles beyond those with a feasible insertion contribute
+                // the largest possible regret so hard-to-place calls go first.
+                let regret: f32 = (1..self.k)
+                    .map(|i| {
+                        per_vehicle_best
+                            .get(i)
+                            .map(|&(cost, _, _, _)| (cost - best_cost) as f32)
+                            .unwrap_or(f32::MAX / (self.k as f32))
+                    })
+                    .sum();
+
+                if best_call.as_ref().map_or(true, |&(_, _, _, _, _, r)| regret > r) {
+                    best_call = Some((call, best_vehicle, best_pickup, best_delivery, best_cost, regret));
+                }
+            }
+
+            match best_call {
+                Some((call, vehicle, pickup_idx, delivery_idx, _, _)) => {
+                    let _ = solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx);
+                }
+                None => {
+                    // No unassigned call has a feasible insertion anywhere;
+                    // stop and leave the remainder in the dummy pool.
+                    infeasible += unassigned.len();
+                    break;
+                }
+            }
+        }
+
+        (evaluated, infeasible)
+    }
+}
+
+/// Aggregation for `Solution::completion_time`'s minimize-arrival-time
+/// objective: whether finishing sooner means the fleet as a whole spends
+/// less total time, or the last vehicle returns sooner (makespan).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionObjective {
+    /// Sum of every route's completion time.
+    Sum,
+    /// The latest completion time across all routes.
+    Max,
+}
+
 #[derive(Debug)]
 pub enum SolutionError {
     InvalidPickupIndex(String),
@@ -16,6 +149,7 @@ pub enum SolutionError {
     CallNotFound(String),
     VehicleOutOfBounds(String),
     InvalidInput(String),
+    LockViolation(String),
 }
 
 #[derive(Clone, Debug)]
@@ -40,6 +174,94 @@ impl Solution {
         )
     }
 
+    /// Builds an initial solution with `mode`'s construction heuristic instead of
+    /// starting from the all-dummy solution. `Greedy`, `Regret2` and `Grasp`
+    /// iterate over unassigned calls, query `get_feasible_insertions` across
+    /// compatible vehicles, and commit insertions via `insert_call`; `Beam`
+    /// builds whole routes with `beam_search_calls`, then runs `repair` and
+    /// `regret_k_recreate`. Calls left without a feasible insertion anywhere
+    /// stay in the dummy pool.
+    pub fn construct(problem: &Problem, mode: ConstructionMode, rng: &mut impl Rng) -> Self {
+        let mut solution = Solution::new(problem);
+
+        match mode {
+            ConstructionMode::Greedy => Self::construct_greedy(&mut solution, problem),
+            ConstructionMode::Regret2 => {
+                regret_k_recreate(&mut solution, problem, 2);
+            }
+            ConstructionMode::Grasp { alpha } => Self::construct_grasp(&mut solution, problem, alpha, rng),
+            ConstructionMode::Beam { width } => {
+                let vehicle_routes = crate::operators::construction::beam_search_calls(problem, width);
+                solution = Solution::from_vehicle_routes(problem, vehicle_routes)
+                    .expect("beam_search_calls returns one route per vehicle with consistent pickup/delivery pairing");
+                // beam_search_calls only checks the legacy weight dimension
+                // while building routes, so a route can come back violating
+                // problem.dimensions or a soft time window; repair() ejects
+                // whatever's causing that and regret_k_recreate reinserts it,
+                // same as the repair half of a ruin-and-recreate operator.
+                solution.repair(problem);
+                regret_k_recreate(&mut solution, problem, 2);
+            }
+        }
+
+        solution
+    }
+
+    /// Greedy cheapest-insertion: each round, inserts whichever unassigned call
+    /// has the single cheapest feasible insertion among all of them.
+    fn construct_greedy(solution: &mut Solution, problem: &Problem) {
+        loop {
+            let unassigned = unassigned_calls(solution);
+            if unassigned.is_empty() {
+                break;
+            }
+
+            let best = unassigned
+                .into_iter()
+                .filter_map(|call| cheapest_insertion_for_call(solution, problem, call).map(|ins| (call, ins)))
+                .min_by_key(|&(_, (cost, _, _, _))| cost);
+
+            match best {
+                Some((call, (_, vehicle, pickup_idx, delivery_idx))) => {
+                    let _ = solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// GRASP: each round, ranks every unassigned call by its cheapest feasible
+    /// insertion and commits a uniformly random pick among the `alpha` cheapest,
+    /// trading some greediness for diversity across restarts.
+    fn construct_grasp(solution: &mut Solution, problem: &Problem, alpha: usize, rng: &mut impl Rng) {
+        let alpha = alpha.max(1);
+
+        loop {
+            let unassigned = unassigned_calls(solution);
+            if unassigned.is_empty() {
+                break;
+            }
+
+            let mut candidates: Vec<(CallId, Cost, VehicleId, usize, usize)> = unassigned
+                .into_iter()
+                .filter_map(|call| {
+                    cheapest_insertion_for_call(solution, problem, call)
+                        .map(|(cost, vehicle, pickup_idx, delivery_idx)| (call, cost, vehicle, pickup_idx, delivery_idx))
+                })
+                .collect();
+
+            if candidates.is_empty() {
+                break;
+            }
+
+            candidates.sort_by_key(|&(_, cost, _, _, _)| cost);
+            let pool_size = alpha.min(candidates.len());
+            let (call, _, vehicle, pickup_idx, delivery_idx) = candidates[rng.random_range(0..pool_size)];
+
+            let _ = solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx);
+        }
+    }
+
     /// Creates a new solution with the given number of vehicles and calls.
     pub(crate) fn from_params(n_vehicles: usize, n_calls: usize) -> Self {
         let routes = vec![Route::new(); n_vehicles];
@@ -53,8 +275,8 @@ impl Solution {
     }
 
     pub fn from_vehicle_routes(problem: &Problem, vehicle_routes: Vec<Vec<CallId>>) -> Result<Self, SolutionError> {
-        let n_vehicles = problem.n_vehicles().get() as usize;
-        let n_calls = problem.n_calls().id() as usize;
+        let n_vehicles = problem.n_vehicles.get() as usize;
+        let n_calls = problem.n_calls.id() as usize;
 
         let mut solution = Solution::from_params(n_vehicles, n_calls);
 
@@ -304,8 +526,13 @@ impl Solution {
     }
 
     /// Inserts a call into a vehicle’s route at the specified delivery index.
+    ///
+    /// Consults `problem.locks` first: the insertion is rejected with
+    /// `SolutionError::LockViolation` if `vehicle` is not allowed to serve
+    /// `call`, or if it would break a declared anchor or sequence constraint.
     pub fn insert_call(
         &mut self,
+        problem: &Problem,
         vehicle: VehicleId,
         call: CallId,
         pickup_idx: usize,
@@ -318,6 +545,42 @@ impl Solution {
             )));
         }
 
+        if !problem.locks.is_vehicle_allowed(call, vehicle) {
+            return Err(SolutionError::LockViolation(format!(
+                "Call {} is locked away from vehicle {:?}",
+                call.id(),
+                vehicle
+            )));
+        }
+
+        let route_calls = self
+            .routes
+            .get(vehicle.index())
+            .ok_or_else(|| SolutionError::VehicleOutOfBounds(format!("Vehicle {:?} not found", vehicle)))?
+            .route();
+
+        if problem.locks.is_anchored_start(call) && pickup_idx != 0 {
+            return Err(SolutionError::LockViolation(format!(
+                "Call {} is anchored to the start of its route",
+                call.id()
+            )));
+        }
+
+        if problem.locks.is_anchored_end(call) && delivery_idx != route_calls.len() {
+            return Err(SolutionError::LockViolation(format!(
+                "Call {} is anchored to the end of its route",
+                call.id()
+            )));
+        }
+
+        if problem.locks.sequence_violation(&route_calls, call, pickup_idx) {
+            return Err(SolutionError::LockViolation(format!(
+                "Call {} would violate a sequence lock at pickup index {}",
+                call.id(),
+                pickup_idx
+            )));
+        }
+
         // If already assigned, remove from its current vehicle.
         if self.assignments[call.index()].is_some() {
             self.remove_call(call)?;
@@ -335,6 +598,11 @@ impl Solution {
     }
 
     /// Removes a call from its vehicle’s route.
+    ///
+    /// Locks only ever constrain *where* an assigned call may go, never
+    /// whether it may be temporarily unassigned, so removal needs no lock
+    /// check of its own — any lock violation is caught when the call is
+    /// reinserted via `insert_call`.
     pub fn remove_call(&mut self, call: CallId) -> Result<(VehicleId, Option<usize>, Option<usize>), SolutionError> {
         let vehicle_ref = &mut self.assignments[call.index()];
 
@@ -366,6 +634,52 @@ impl Solution {
         self.routes[vehicle.index()].route()
     }
 
+    /// A canonical hash of this solution's route assignments *and order*,
+    /// built by packing every vehicle's call sequence into a `Matrix2<i16>`
+    /// (padded with `0`, an id no real call ever takes) and reusing its
+    /// byte-wise `Hash`. Unlike the `Hash` impl below, which only captures
+    /// which vehicle serves each call (for `SearchProgress`'s frequency
+    /// tracking), this also distinguishes two solutions that assign the
+    /// same calls to the same vehicles in a different sequence — the
+    /// distinction `Pooled`'s visited-solution cache needs to avoid treating
+    /// two different routes as the same evaluated candidate.
+    pub fn route_hash(&self, problem: &Problem) -> u64 {
+        let n_vehicles = problem.n_vehicles().get() as usize;
+        let routes: Vec<Vec<CallId>> = (1..=n_vehicles)
+            .map(|i| self.route(VehicleId::new(i as u8).unwrap()))
+            .collect();
+        let max_len = routes.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut encoded = Matrix2::new(n_vehicles, max_len, 0i16);
+        for (row, route) in routes.iter().enumerate() {
+            for (col, call) in route.iter().enumerate() {
+                *encoded.get_mut(row, col) = call.id();
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        encoded.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Rebuilds this solution (found over a `Problem::cluster`-reduced
+    /// problem) into a solution over the `original` problem, replacing every
+    /// cluster call with its members via `map`. The expanded route for each
+    /// vehicle stays properly nested (no crossing pickups/deliveries), so the
+    /// caller can revalidate it with `feasible`/`cost` on `original` exactly
+    /// like any other solution.
+    pub fn expand_clusters(&self, original: &Problem, map: &ClusterMap) -> Solution {
+        let mut expanded = Solution::new(original);
+
+        for (i, route) in self.routes.iter().enumerate() {
+            let vehicle = VehicleId::new((i + 1) as u8).expect("VehicleId must be nonzero");
+            let sequence = map.expand(&route.route());
+            insert_nested_sequence(&mut expanded, original, vehicle, &sequence);
+        }
+
+        expanded
+    }
+
     /// Checks whether the specified call is unassigned.
     pub fn is_unassigned(&self, call: CallId) -> bool {
         self.assignments[call.index()].is_none()
@@ -403,7 +717,7 @@ impl Solution {
 
         (
             call_weight,
-            self.routes[vehicle.index()].find_spare_capacity(problem, call_weight, vehicle),
+            self.routes[vehicle.index()].find_spare_capacity(problem, call, vehicle),
         )
     }
 
@@ -420,6 +734,56 @@ impl Solution {
             .flatten()
     }
 
+    /// Like `get_feasible_insertions`, but ranks the feasible pairs by
+    /// estimated insertion cost (cheapest first) instead of enumeration
+    /// order. See `feasibility::ranked_insertions` for how cost is
+    /// estimated; used by `RegretConstructor` to avoid a full
+    /// insert/`cost`/remove cycle per candidate.
+    pub fn ranked_insertions(
+        &self,
+        problem: &Problem,
+        call: CallId,
+        vehicle: VehicleId,
+        capacity_result: &Option<CapacityResult>,
+    ) -> Vec<(usize, usize, Cost)> {
+        ranked_insertions(problem, self, vehicle, call, capacity_result)
+    }
+
+    /// Like `get_feasible_insertions`, but bounded to at most `width`
+    /// candidate positions instead of every feasible pair -- see
+    /// `FeasibleInsertions::with_beam` for how positions are scored and
+    /// pruned. Trades completeness for runtime on large routes where the
+    /// full scan dominates.
+    pub fn beamed_feasible_insertions<'a>(
+        &'a self,
+        problem: &'a Problem,
+        call: CallId,
+        vehicle: VehicleId,
+        capacity_result: &'a Option<CapacityResult>,
+        width: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        FeasibleInsertions::new(problem, self, vehicle, call, capacity_result)
+            .map(move |insertions| insertions.with_beam(width))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Up to `k` calls nearest to `call`, closest-first -- a thin delegation
+    /// to `Problem::nearest_calls`. Solution-independent (the underlying
+    /// distance is the same static relatedness term `relatedness_terms`
+    /// exposes), exposed here alongside the other removal-facing queries so
+    /// callers holding a `Solution` don't need to reach into `Problem`
+    /// themselves.
+    pub fn nearest_calls(&self, problem: &Problem, call: CallId, k: usize) -> &[(CallId, f32)] {
+        problem.nearest_calls(call, k)
+    }
+
+    /// Every call within `max_distance` of `call` -- a thin delegation to
+    /// `Problem::calls_within`. See `nearest_calls`.
+    pub fn calls_within(&self, problem: &Problem, call: CallId, max_distance: f32) -> &[(CallId, f32)] {
+        problem.calls_within(call, max_distance)
+    }
+
     /// Checks whether the solution is feasible with respect to the given problem.
     ///
     /// For each vehicle’s route, we simulate the schedule:
@@ -461,9 +825,134 @@ impl Solution {
                 )));
             }
         }
+
+        if !problem.locks.is_empty() {
+            self.check_locks(problem)?;
+        }
+
+        Ok(())
+    }
+
+    /// Verifies every route against `problem.locks`: vehicle pins/forbids,
+    /// start/end anchors, sequence-before ordering, and locked call groups.
+    fn check_locks(&self, problem: &Problem) -> Result<(), SolutionError> {
+        for (i, route) in self.routes.iter().enumerate() {
+            let vehicle_id = VehicleId::new((i + 1) as u8).expect("VehicleId must be nonzero");
+            let route_calls = route.route();
+
+            if problem.locks.group_violation(vehicle_id, &route_calls) {
+                return Err(SolutionError::LockViolation(format!(
+                    "Vehicle {}'s route violates a locked call-group constraint",
+                    vehicle_id.get()
+                )));
+            }
+
+            for (pos, &call) in route_calls.iter().enumerate() {
+                if !call.is_pickup() {
+                    continue;
+                }
+
+                if !problem.locks.is_vehicle_allowed(call, vehicle_id) {
+                    return Err(SolutionError::LockViolation(format!(
+                        "Call {} is served by vehicle {} but is locked away from it",
+                        call.id(),
+                        vehicle_id.get()
+                    )));
+                }
+
+                if problem.locks.is_anchored_start(call) && pos != 0 {
+                    return Err(SolutionError::LockViolation(format!(
+                        "Call {} must be the first stop of vehicle {}'s route",
+                        call.id(),
+                        vehicle_id.get()
+                    )));
+                }
+
+                if problem.locks.is_anchored_end(call) {
+                    let delivery_pos = route_calls
+                        .iter()
+                        .rposition(|&c| c == call.delivery())
+                        .expect("delivery must exist alongside its pickup");
+                    if delivery_pos != route_calls.len() - 1 {
+                        return Err(SolutionError::LockViolation(format!(
+                            "Call {} must be the last stop of vehicle {}'s route",
+                            call.id(),
+                            vehicle_id.get()
+                        )));
+                    }
+                }
+
+                if problem.locks.sequence_violation(&route_calls, call, pos) {
+                    return Err(SolutionError::LockViolation(format!(
+                        "Call {} violates a sequence-before lock in vehicle {}'s route",
+                        call.id(),
+                        vehicle_id.get()
+                    )));
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Greedily restores feasibility on a solution whose routes may violate
+    /// capacity or time windows: walks each route in order (see
+    /// `feasibility::route_violation`), and while it remains infeasible,
+    /// ejects whichever assigned call (pickup+delivery pair) relieves the
+    /// largest violation — capacity overflow plus time-window lateness —
+    /// re-running the walk until the route is clean. Ejected calls are
+    /// removed via `remove_call` and their route's storage is defragmented
+    /// with `compact_iter`. Returns every ejected call so a follow-up
+    /// insertion heuristic (e.g. `regret_k_recreate`) can reassign them,
+    /// making this usable as the repair half of a ruin-and-recreate operator.
+    pub fn repair(&mut self, problem: &Problem) -> Vec<CallId> {
+        let mut ejected = Vec::new();
+
+        for i in 0..self.routes.len() {
+            let vehicle_id = VehicleId::new((i + 1) as u8).expect("VehicleId must be nonzero");
+
+            loop {
+                let route_calls = self.routes[i].route();
+                let Some((violation_idx, _)) = route_violation(problem, vehicle_id, &route_calls) else {
+                    break;
+                };
+
+                let candidates: HashSet<CallId> = route_calls[..=violation_idx]
+                    .iter()
+                    .map(|c| c.pickup())
+                    .collect();
+
+                let mut best: Option<(CallId, i64)> = None;
+                for call in candidates {
+                    let without: Vec<CallId> = route_calls
+                        .iter()
+                        .copied()
+                        .filter(|c| c.pickup() != call)
+                        .collect();
+                    let remaining = route_violation(problem, vehicle_id, &without)
+                        .map_or(0, |(_, total)| total);
+
+                    if best.map_or(true, |(_, best_remaining)| remaining < best_remaining) {
+                        best = Some((call, remaining));
+                    }
+                }
+
+                let Some((call, _)) = best else {
+                    // `candidates` is never empty (it always contains at least
+                    // the call at `violation_idx`), but bail out rather than
+                    // loop forever if that invariant is ever violated.
+                    break;
+                };
+
+                let _ = self.remove_call(call);
+                self.routes[i].compact_iter().for_each(drop);
+                ejected.push(call);
+            }
+        }
+
+        ejected
+    }
+
     /// Computes the total cost of the solution.
     /// For each route (vehicle), the cost is the sum of its route cost and port cost as computed by simulate().
     pub fn cost(&mut self, problem: &Problem) -> Cost {
@@ -477,7 +966,7 @@ impl Solution {
             if let Some(sim) = route.last_simulation() {
                 if sim.is_feasible {
                     // If we have a feasible simulation result, use its cost values
-                    total_cost += sim.route_cost + sim.port_cost;
+                    total_cost += sim.route_cost + sim.port_cost + sim.soft_penalty;
                     continue;
                 }
                 // If simulation exists but is infeasible, re-simulate (fallthrough)
@@ -486,7 +975,7 @@ impl Solution {
             // No simulation result yet or existing simulation is infeasible, so run simulate
             if route.simulate(problem, vehicle_id, Some(self.costs.as_mut())) {
                 let sim = route.last_simulation().unwrap();
-                total_cost += sim.route_cost + sim.port_cost;
+                total_cost += sim.route_cost + sim.port_cost + sim.soft_penalty;
             }
         }
 
@@ -510,6 +999,154 @@ impl Solution {
     pub fn call_costs(&self) -> &Vec<CallCost> {
         &self.costs
     }
+
+    /// Computes the minimize-arrival-time objective: the `objective`
+    /// aggregation (sum or max) of each route's completion time, mirroring
+    /// the arrival-time objective other VRP engines use to favor schedules
+    /// where all work finishes sooner. A route with no stops completes at
+    /// its vehicle's starting time.
+    pub fn completion_time(&mut self, problem: &Problem, objective: CompletionObjective) -> Time {
+        let times: Vec<Time> = self
+            .routes
+            .iter_mut()
+            .enumerate()
+            .map(|(i, route)| {
+                let vehicle_id = VehicleId::new((i + 1) as u8).expect("VehicleId must be nonzero");
+
+                if route.last_simulation().is_none() {
+                    route.simulate(problem, vehicle_id, None);
+                }
+
+                route.completion_time()
+            })
+            .collect();
+
+        match objective {
+            CompletionObjective::Sum => times.iter().sum(),
+            CompletionObjective::Max => times.into_iter().max().unwrap_or(0),
+        }
+    }
+
+    /// Reclaims idle time across every route (see `Route::optimize_schedule`)
+    /// and returns the total waiting time reclaimed, so an objective can
+    /// favor compact, low-idle schedules over otherwise-equal ones.
+    pub fn optimize_schedule(&mut self, problem: &Problem) -> Time {
+        self.routes
+            .iter_mut()
+            .enumerate()
+            .map(|(i, route)| {
+                let vehicle_id = VehicleId::new((i + 1) as u8).expect("VehicleId must be nonzero");
+
+                if route.last_simulation().is_none() {
+                    route.simulate(problem, vehicle_id, None);
+                }
+
+                route.optimize_schedule()
+            })
+            .sum()
+    }
+}
+
+/// Collects the currently-unassigned calls as pickup `CallId`s.
+fn unassigned_calls(solution: &Solution) -> Vec<CallId> {
+    solution
+        .call_assignments()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, assignment)| {
+            if assignment.is_none() {
+                CallId::new_pickup((idx + 1) as i16)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Finds `call`'s cheapest feasible insertion across its compatible vehicles by
+/// trial-inserting each candidate position, measuring `solution.cost`, and
+/// reverting, the same evaluation idiom `regret_k_recreate` uses.
+fn cheapest_insertion_for_call(
+    solution: &mut Solution,
+    problem: &Problem,
+    call: CallId,
+) -> Option<(Cost, VehicleId, usize, usize)> {
+    let mut best: Option<(Cost, VehicleId, usize, usize)> = None;
+
+    for &vehicle in problem.get_compatible_vehicles(call.pickup()) {
+        let (_, capacity_result) = solution.find_spare_capacity_in_vehicle(problem, call, vehicle);
+        if capacity_result.is_none() {
+            continue;
+        }
+        let capacity_result = capacity_result.clone();
+
+        let candidates: Vec<(usize, usize)> = solution
+            .get_feasible_insertions(problem, call, vehicle, &capacity_result)
+            .collect();
+
+        for (pickup_idx, delivery_idx) in candidates {
+            if solution.insert_call(problem, vehicle, call, pickup_idx, delivery_idx).is_err() {
+                continue;
+            }
+
+            if solution.feasible(problem).is_err() {
+                let _ = solution.remove_call(call);
+                continue;
+            }
+
+            let cost = solution.cost(problem);
+            let _ = solution.remove_call(call);
+
+            if best.map_or(true, |(c, _, _, _)| cost < c) {
+                best = Some((cost, vehicle, pickup_idx, delivery_idx));
+            }
+        }
+    }
+
+    best
+}
+
+/// Inserts a properly-nested (non-crossing) flat pickup/delivery `sequence`
+/// into `vehicle`'s route in `solution`, via one `insert_call` per distinct
+/// call. Used by `Solution::expand_clusters`, where an expanded cluster
+/// still nests cleanly inside whatever the reduced route's stops were.
+///
+/// Calls are committed in order of their delivery position: since the
+/// sequence never crosses, every already-committed call is either entirely
+/// before the next call's pickup or entirely nested inside it, so its
+/// logical pickup/delivery indices can be derived from how many committed
+/// calls fall on each side.
+fn insert_nested_sequence(solution: &mut Solution, problem: &Problem, vehicle: VehicleId, sequence: &[CallId]) {
+    let mut pickup_pos = HashMap::new();
+    let mut delivery_pos = HashMap::new();
+
+    for (pos, &call) in sequence.iter().enumerate() {
+        if call.is_pickup() {
+            pickup_pos.insert(call, pos);
+        } else {
+            delivery_pos.insert(call.pickup(), pos);
+        }
+    }
+
+    let mut order: Vec<CallId> = pickup_pos.keys().copied().collect();
+    order.sort_by_key(|call| delivery_pos[call]);
+
+    let mut committed: Vec<usize> = Vec::with_capacity(order.len());
+
+    for call in order {
+        let this_pickup_pos = pickup_pos[&call];
+        let before = committed.iter().filter(|&&p| p < this_pickup_pos).count();
+        let nested = committed.len() - before;
+
+        let pickup_idx = 2 * before;
+        let delivery_idx = pickup_idx + 1 + 2 * nested;
+
+        solution
+            .insert_call(problem, vehicle, call, pickup_idx, delivery_idx)
+            .expect("expanded cluster sequence must stay a valid nested insertion");
+
+        committed.push(this_pickup_pos);
+    }
 }
 
 impl Hash for Solution {
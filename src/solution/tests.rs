@@ -8,6 +8,7 @@ mod solution_tests {
 
     #[test]
     fn test_insert_and_remove_calls() {
+        let problem = Problem::minimal(3, 5);
         let mut solution = Solution::from_params(3, 5);
 
         let v1 = VehicleId::new(1).unwrap();
@@ -21,11 +22,11 @@ mod solution_tests {
         let c5 = CallId::new_pickup(5).unwrap();
 
         // Insert calls in a certain order
-        solution.insert_call(v1, c1, 0, 1).unwrap();
-        solution.insert_call(v1, c2, 1, 2).unwrap();
-        solution.insert_call(v2, c3, 0, 1).unwrap();
-        solution.insert_call(v3, c4, 0, 2).unwrap();
-        solution.insert_call(v3, c5, 0, 2).unwrap();
+        solution.insert_call(&problem, v1, c1, 0, 1).unwrap();
+        solution.insert_call(&problem, v1, c2, 1, 2).unwrap();
+        solution.insert_call(&problem, v2, c3, 0, 1).unwrap();
+        solution.insert_call(&problem, v3, c4, 0, 2).unwrap();
+        solution.insert_call(&problem, v3, c5, 0, 2).unwrap();
 
         // Check expected routes
         assert_eq!(solution.route(v1), vec![c1, c2, c1.inverse(), c2.inverse()]);
@@ -40,8 +41,8 @@ mod solution_tests {
         assert_eq!(solution.route(v3), vec![c5, c5.inverse()]);
 
         // Reinsert in different orders
-        solution.insert_call(v1, c5, 1, 2).unwrap();
-        solution.insert_call(v2, c2, 0, 1).unwrap();
+        solution.insert_call(&problem, v1, c5, 1, 2).unwrap();
+        solution.insert_call(&problem, v2, c2, 0, 1).unwrap();
 
         // Verify final routes
         assert_eq!(solution.route(v1), vec![c1, c5, c1.inverse(), c5.inverse()]);
@@ -51,20 +52,67 @@ mod solution_tests {
 
     #[test]
     fn test_invalid_insertions() {
+        let problem = Problem::minimal(2, 3);
         let mut solution = Solution::from_params(2, 3);
 
         let v1 = VehicleId::new(1).unwrap();
         let c1 = CallId::new_pickup(1).unwrap();
 
         // Attempt to insert with delivery before pickup (should fail)
-        assert!(solution.insert_call(v1, c1, 2, 1).is_err());
+        assert!(solution.insert_call(&problem, v1, c1, 2, 1).is_err());
 
         // Attempt to remove a call that hasn't been inserted (should fail)
         assert!(solution.remove_call(c1).is_err());
     }
 
+    #[test]
+    fn test_dimension_capacity_boundary() {
+        use crate::problem::dimension::Dimension;
+
+        let mut problem = Problem::minimal(1, 2);
+        let v1 = VehicleId::new(1).unwrap();
+        let c1 = CallId::new_pickup(1).unwrap();
+        let c2 = CallId::new_pickup(2).unwrap();
+
+        // Bound exactly matches the combined pickup load: feasible, since
+        // the check in `Route::simulate` is strict `>`.
+        problem.dimensions.push(Dimension::new("pallets", vec![3, 2], vec![5]));
+
+        let mut solution = Solution::from_params(1, 2);
+        solution.insert_call(&problem, v1, c1, 0, 1).unwrap();
+        solution.insert_call(&problem, v1, c2, 1, 2).unwrap();
+
+        assert!(
+            solution.feasible(&problem).is_ok(),
+            "load exactly at the bound should be feasible"
+        );
+    }
+
+    #[test]
+    fn test_dimension_capacity_exceeded() {
+        use crate::problem::dimension::Dimension;
+
+        let mut problem = Problem::minimal(1, 2);
+        let v1 = VehicleId::new(1).unwrap();
+        let c1 = CallId::new_pickup(1).unwrap();
+        let c2 = CallId::new_pickup(2).unwrap();
+
+        // One unit over the bound: infeasible.
+        problem.dimensions.push(Dimension::new("pallets", vec![3, 3], vec![5]));
+
+        let mut solution = Solution::from_params(1, 2);
+        solution.insert_call(&problem, v1, c1, 0, 1).unwrap();
+        solution.insert_call(&problem, v1, c2, 1, 2).unwrap();
+
+        assert!(
+            solution.feasible(&problem).is_err(),
+            "load exceeding the bound should be infeasible"
+        );
+    }
+
     #[test]
     fn test_reassign_call() {
+        let problem = Problem::minimal(2, 3);
         let mut solution = Solution::from_params(2, 3);
 
         let v1 = VehicleId::new(1).unwrap();
@@ -72,10 +120,10 @@ mod solution_tests {
         let c1 = CallId::new_pickup(1).unwrap();
 
         // Insert a call in one vehicle, then reassign it
-        solution.insert_call(v1, c1, 0, 1).unwrap();
+        solution.insert_call(&problem, v1, c1, 0, 1).unwrap();
         assert_eq!(solution.route(v1), vec![c1, c1.inverse()]);
 
-        solution.insert_call(v2, c1, 0, 1).unwrap();
+        solution.insert_call(&problem, v2, c1, 0, 1).unwrap();
         assert_eq!(solution.route(v1), vec![]); // Should be removed from v1
         assert_eq!(solution.route(v2), vec![c1, c1.inverse()]);
     }
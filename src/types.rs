@@ -1,11 +1,26 @@
 use std::num::{NonZeroI16, NonZeroU8};
 
+use rand::RngCore;
+
+use crate::operators::params::RemovalParams;
+use crate::problem::Problem;
+use crate::solution::Solution;
+
 pub type NodeId = u8;
 pub type Time = i16;
 pub type Capacity = i32;
 pub type Cost = i32;
 pub type CargoSize = u16;
 
+/// One adaptive-selection unit in `search::alns::ALNS::operator_combinations`:
+/// a removal operator (`REMOVAL_OPERATORS`'s signature) paired with the
+/// insertion operator (`operators::INSERTION_OPERATORS`'s signature) that
+/// repairs whatever it destroys.
+pub type OperatorPair = (
+    fn(&Solution, &Problem, &mut dyn RngCore, &RemovalParams) -> Vec<CallId>,
+    fn(&mut Solution, &Problem, Vec<CallId>) -> (usize, usize),
+);
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct CallId(NonZeroI16);
@@ -39,6 +54,14 @@ impl CallId {
         self.0.get()
     }
 
+    /// Reconstructs a `CallId` from a value previously returned by `raw`
+    /// (positive for a pickup, negative for its delivery). `None` if `raw`
+    /// is zero.
+    #[inline(always)]
+    pub fn from_raw(raw: i16) -> Option<Self> {
+        NonZeroI16::new(raw).map(CallId)
+    }
+
     #[inline(always)]
     pub fn pickup(self) -> Self {
         CallId(NonZeroI16::new(self.raw().abs()).unwrap())
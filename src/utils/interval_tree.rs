@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::RangeBounds;
 use std::ops::RangeInclusive;
 
@@ -8,6 +8,8 @@ use crate::types::{CallId, Time};
 pub struct IntervalTree {
     index_by_start: BTreeMap<Time, Vec<(Time, CallId)>>,
     index_by_end: BTreeMap<Time, Vec<(Time, CallId)>>,
+    /// Each indexed call's window, so `remove` doesn't need it passed back in.
+    windows: HashMap<CallId, RangeInclusive<Time>>,
 }
 
 impl IntervalTree {
@@ -21,6 +23,7 @@ impl IntervalTree {
     {
         let mut index_by_start = BTreeMap::new();
         let mut index_by_end = BTreeMap::new();
+        let mut windows = HashMap::new();
 
         for (call_id, window) in iter {
             let start = *window.start();
@@ -33,11 +36,64 @@ impl IntervalTree {
                 .entry(end)
                 .or_insert_with(Vec::new)
                 .push((start, call_id));
+            windows.insert(call_id, window);
         }
 
         Self {
             index_by_start,
             index_by_end,
+            windows,
+        }
+    }
+
+    /// Returns whether `call` is currently indexed.
+    pub fn contains(&self, call: CallId) -> bool {
+        self.windows.contains_key(&call)
+    }
+
+    /// Every indexed `(call, window)` pair, in no particular order. Feeding
+    /// this back into `new` reconstructs an equivalent tree; used by
+    /// `problem::cache` to serialize a tree without reaching into its
+    /// private `BTreeMap` layout.
+    pub fn entries(&self) -> impl Iterator<Item = (CallId, RangeInclusive<Time>)> + '_ {
+        self.windows.iter().map(|(&call, window)| (call, window.clone()))
+    }
+
+    /// Indexes `call` under `window`, keeping `index_by_start` and
+    /// `index_by_end` consistent. If `call` is already indexed, its previous
+    /// entry is removed first, so re-inserting a call moves it rather than
+    /// duplicating it.
+    pub fn insert(&mut self, call: CallId, window: RangeInclusive<Time>) {
+        self.remove(call);
+
+        let start = *window.start();
+        let end = *window.end();
+
+        self.index_by_start.entry(start).or_insert_with(Vec::new).push((end, call));
+        self.index_by_end.entry(end).or_insert_with(Vec::new).push((start, call));
+        self.windows.insert(call, window);
+    }
+
+    /// Removes `call`'s interval, if indexed, from both `index_by_start` and
+    /// `index_by_end`, dropping either `BTreeMap` entry once it's left empty.
+    /// A no-op if `call` isn't indexed.
+    pub fn remove(&mut self, call: CallId) {
+        let Some(window) = self.windows.remove(&call) else {
+            return;
+        };
+
+        Self::remove_entry(&mut self.index_by_start, *window.start(), call);
+        Self::remove_entry(&mut self.index_by_end, *window.end(), call);
+    }
+
+    /// Drops `call` from the `Vec` keyed by `key` in `map`, removing the key
+    /// entirely once its `Vec` is empty.
+    fn remove_entry(map: &mut BTreeMap<Time, Vec<(Time, CallId)>>, key: Time, call: CallId) {
+        if let Some(entries) = map.get_mut(&key) {
+            entries.retain(|&(_, c)| c != call);
+            if entries.is_empty() {
+                map.remove(&key);
+            }
         }
     }
 
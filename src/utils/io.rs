@@ -3,6 +3,8 @@ use std::path::Path;
 
 use clap::Parser;
 
+use crate::types::{Cost, Time};
+
 #[derive(Parser)]
 pub struct Args {
     /// Path to a directory containing problem files, or a base path for problem files
@@ -49,6 +51,85 @@ pub struct Args {
     /// Optional delay in seconds to print the current best solution after it improved
     #[arg(long)]
     pub print_best_delay: Option<u32>,
+
+    /// Beam width for `operators::construction::beam_search_calls`'s warm start
+    #[arg(long, default_value_t = 1)]
+    pub beam_width: usize,
+
+    /// Directory for `Problem::load_with_cache`'s precomputed ProblemIndex
+    /// files; defaults to `problem::cache::DEFAULT_CACHE_DIR` when unset
+    #[arg(long)]
+    pub cache_dir: Option<String>,
+
+    /// Ignore any cached ProblemIndex and force a fresh rebuild (still
+    /// overwrites the cache entry with the freshly rebuilt index)
+    #[arg(long, default_value_t = false)]
+    pub force_recompute_index: bool,
+
+    /// Close the cost model with a final depot leg per route
+    /// (`Problem::return_to_depot`), required for `--completion-time-objective`
+    /// to mean arrival back home rather than the last stop's departure
+    #[arg(long, default_value_t = false)]
+    pub return_to_depot: bool,
+
+    /// Rank and report solutions by `Solution::completion_time` (minimize
+    /// arrival time) instead of `Solution::cost`; implies `--return-to-depot`
+    #[arg(long, default_value_t = false)]
+    pub completion_time_objective: bool,
+
+    /// With `--completion-time-objective`, aggregate routes by their latest
+    /// completion time (makespan) instead of the default sum of all routes
+    #[arg(long, default_value_t = false)]
+    pub completion_time_makespan: bool,
+
+    /// Switch `Problem::time_window_policy` to `Soft`: late arrivals are
+    /// charged this cost per unit of time instead of aborting the route
+    /// (`Route::simulate`'s `SimulationResult::soft_penalty`)
+    #[arg(long)]
+    pub soft_time_window_late_penalty: Option<Cost>,
+
+    /// With `--soft-time-window-late-penalty`, also charge early arrivals
+    /// this cost per unit of time instead of making the vehicle wait for
+    /// the window to open
+    #[arg(long)]
+    pub soft_time_window_early_penalty: Option<Cost>,
+
+    /// Run `Problem::cluster`'s vicinity-clustering preprocessing pass
+    /// before solving, so the search operates on a reduced instance
+    #[arg(long, default_value_t = false)]
+    pub cluster: bool,
+
+    /// `ClusteringPolicy::duration_threshold`
+    #[arg(long, default_value_t = 30)]
+    pub cluster_duration_threshold: Time,
+
+    /// `ClusteringPolicy::distance_threshold`
+    #[arg(long, default_value_t = 100)]
+    pub cluster_distance_threshold: Cost,
+
+    /// `ClusteringPolicy::min_window_overlap`
+    #[arg(long, default_value_t = 0)]
+    pub cluster_min_window_overlap: Time,
+
+    /// `ClusteringPolicy::max_cluster_size`
+    #[arg(long, default_value_t = 4)]
+    pub cluster_max_size: usize,
+
+    /// Number of `search::pooled::run_islands` islands to run side by side
+    /// per tick instead of a single `Pooled` search; `1` takes the
+    /// single-island path
+    #[arg(long, default_value_t = 1)]
+    pub islands: usize,
+
+    /// With `--islands`, iterations between an island's check-ins with its
+    /// leader board(s) (`IslandParams::migration_interval`)
+    #[arg(long, default_value_t = 200)]
+    pub migration_interval: usize,
+
+    /// With `--islands`, wire islands in a ring (`IslandTopology::Ring`)
+    /// instead of all sharing one leader board (`FullyConnected`, the default)
+    #[arg(long, default_value_t = false)]
+    pub ring_topology: bool,
 }
 
 pub fn enumerate_input_files(args: &Args) -> io::Result<Vec<std::path::PathBuf>> {
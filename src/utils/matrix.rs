@@ -24,6 +24,12 @@ impl<T: Clone> Matrix2<T> {
     }
 }
 
+impl<T: Clone + Default> Default for Matrix2<T> {
+    fn default() -> Self {
+        Matrix2::new(0, 0, T::default())
+    }
+}
+
 // Implement PartialEq, Eq and Hash using a byte-wise comparison.
 impl<T: Pod> PartialEq for Matrix2<T> {
     fn eq(&self, other: &Self) -> bool {